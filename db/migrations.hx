@@ -0,0 +1,305 @@
+// ============================================================================
+// Helix DB Migrations for Lighthouse Journey Timeline
+// Converges the legacy schema variant (schema::1) onto schema::2
+// ============================================================================
+
+// ============================================================================
+// LEGACY SCHEMA (only the definitions that differ from schema::2)
+// ============================================================================
+
+schema::1 {
+    // Session node (timestamps stored as String, counters as U32, owner as a user_key property)
+    N::Session {
+        external_id: String,
+        user_key: String,
+        start_time: String,
+        end_time: String,
+        duration_seconds: U32,
+        screenshot_count: U32,
+        workflow_primary: String,
+        workflow_secondary: String,
+        workflow_confidence: F64,
+        metadata: String
+    }
+
+    // Activity node (timestamp stored as String)
+    N::Activity {
+        screenshot_external_id: String,
+        workflow_tag: String,
+        timestamp: String,
+        summary: String,
+        confidence: F64,
+        metadata: String
+    }
+
+    // WorkflowPattern node (ownership stored only as a user_id property, U32 counter)
+    N::WorkflowPattern {
+        user_id: String,
+        intent_category: String,
+        occurrence_count: U32,
+        metadata: String
+    }
+
+    // Block node (ownership stored only as a user_id property, U32 counter)
+    N::Block {
+        user_id: String,
+        canonical_slug: String,
+        intent_label: String,
+        primary_tool: String,
+        occurrence_count: U32,
+        metadata: String
+    }
+}
+
+// ============================================================================
+// MIGRATIONS
+// ============================================================================
+
+MIGRATION schema::1 => schema::2 {
+    // String timestamps -> Date, so range comparisons and ordering work
+    // U32 counters -> I64, the canonical width for every count/duration property
+    N::Session => _::{
+        external_id: external_id,
+        user_key: user_key,
+        start_time: start_time AS Date,
+        end_time: end_time AS Date,
+        duration_seconds: duration_seconds AS I64,
+        screenshot_count: screenshot_count AS I64,
+        workflow_primary: workflow_primary,
+        workflow_secondary: workflow_secondary,
+        workflow_confidence: workflow_confidence,
+        metadata: metadata
+    }
+
+    N::Activity => _::{
+        screenshot_external_id: screenshot_external_id,
+        workflow_tag: workflow_tag,
+        timestamp: timestamp AS Date,
+        summary: summary,
+        confidence: confidence,
+        metadata: metadata
+    }
+
+    // Keep user_id so BackfillPatternAndBlockOwnership can create the ownership edges
+    N::WorkflowPattern => _::{
+        user_id: user_id,
+        intent_category: intent_category,
+        occurrence_count: occurrence_count AS I64,
+        metadata: metadata
+    }
+
+    N::Block => _::{
+        user_id: user_id,
+        canonical_slug: canonical_slug,
+        intent_label: intent_label,
+        primary_tool: primary_tool,
+        occurrence_count: occurrence_count AS I64,
+        metadata: metadata
+    }
+}
+
+// ============================================================================
+// SCHEMA VERSION
+// ============================================================================
+
+// Record the schema revision after `MIGRATION schema::1 => schema::2` has been applied
+QUERY RecordSchemaVersion(version: I64, migration: String) =>
+    existing <- N<SchemaVersion>
+    schema_version <- existing::UpsertN({version: version, last_migration: migration, updated_at: NOW})
+    RETURN schema_version
+
+QUERY GetSchemaVersion() =>
+    schema_version <- N<SchemaVersion>
+    RETURN schema_version
+
+// ============================================================================
+// DATA MIGRATIONS (run once after the schema migration)
+// ============================================================================
+
+// Create UserHasPattern/UserHasBlock edges for legacy rows that only carry a user_id property
+QUERY BackfillPatternAndBlockOwnership() =>
+    patterns <- N<WorkflowPattern>::WHERE(AND(_::{user_id}::NEQ(""), !EXISTS(_::In<UserHasPattern>)))
+    FOR pattern IN patterns {
+        pattern_owner <- N<User>({external_id: pattern::{user_id}})
+        AddE<UserHasPattern>::From(pattern_owner)::To(pattern)
+    }
+    blocks <- N<Block>::WHERE(AND(_::{user_id}::NEQ(""), !EXISTS(_::In<UserHasBlock>)))
+    FOR block IN blocks {
+        block_owner <- N<User>({external_id: block::{user_id}})
+        AddE<UserHasBlock>::From(block_owner)::To(block)
+    }
+    existing_version <- N<SchemaVersion>
+    existing_version::UpsertN({version: 2, last_migration: "BackfillPatternAndBlockOwnership", updated_at: NOW})
+    RETURN "Success"
+
+// Create UserOwnsSession edges for sessions that only carry a user_key property
+QUERY BackfillSessionOwnership() =>
+    sessions <- N<Session>::WHERE(AND(_::{user_key}::NEQ(""), !EXISTS(_::In<UserOwnsSession>)))
+    FOR session IN sessions {
+        owner <- N<User>({external_id: session::{user_key}})
+        AddE<UserOwnsSession>::From(owner)::To(session)
+    }
+    existing_version <- N<SchemaVersion>
+    existing_version::UpsertN({version: 2, last_migration: "BackfillSessionOwnership", updated_at: NOW})
+    RETURN "Success"
+
+// Create the NodeContainsSession inverse for SessionInNode edges written before it existed.
+// A session needs work when it has more SessionInNode edges than incoming NodeContainsSession edges
+QUERY DeriveNodeContainsSessionEdges() =>
+    sessions <- N<Session>::WHERE(_::Out<SessionInNode>::COUNT::GT(_::In<NodeContainsSession>::COUNT))
+    FOR session IN sessions {
+        nodes <- session::Out<SessionInNode>::WHERE(!EXISTS(_::Out<NodeContainsSession>::WHERE(_::{external_id}::EQ(session::{external_id}))))
+        FOR node IN nodes {
+            AddE<NodeContainsSession>::From(node)::To(session)
+        }
+    }
+    existing_version <- N<SchemaVersion>
+    existing_version::UpsertN({version: 2, last_migration: "DeriveNodeContainsSessionEdges", updated_at: NOW})
+    RETURN "Success"
+
+// Rewrite legacy RelatesTo edges as ActivityRelatedToConcept so reads only need one label
+QUERY ConsolidateRelatesToEdges() =>
+    legacy <- E<RelatesTo>
+    FOR edge IN legacy {
+        activity <- edge::FromN
+        concept <- edge::ToN
+        existing <- activity::OutE<ActivityRelatedToConcept>::WHERE(_::ToN::{name}::EQ(concept::{name}))
+        existing::UpsertE({relevance: edge::{relevance}})::From(activity)::To(concept)
+    }
+    DROP E<RelatesTo>
+    existing_version <- N<SchemaVersion>
+    existing_version::UpsertN({version: 2, last_migration: "ConsolidateRelatesToEdges", updated_at: NOW})
+    RETURN "Success"
+
+// Set Activity.day (UTC YYYY-MM-DD of timestamp) on activities written before it existed. HQL cannot
+// format a Date, so the caller pages through GetActivitiesMissingDay and supplies the day strings
+QUERY BackfillActivityDays(activities: [{screenshot_external_id: String, day: String}]) =>
+    FOR {screenshot_external_id, day} IN activities {
+        N<Activity>({screenshot_external_id: screenshot_external_id})::WHERE(_::{day}::EQ(""))::UPDATE({day: day})
+    }
+    existing_version <- N<SchemaVersion>
+    existing_version::UpsertN({version: 2, last_migration: "BackfillActivityDays", updated_at: NOW})
+    RETURN "Success"
+
+// Stamp created_at/updated_at on nodes written before these fields existed. Nodes that already carry
+// created_at are left alone; sessions and activities take their own start_time/timestamp
+QUERY BackfillNodeTimestamps() =>
+    FOR session IN N<Session>::WHERE(!EXISTS(_::{created_at})) {
+        session::UPDATE({created_at: session::{start_time}, updated_at: session::{end_time}})
+    }
+    FOR activity IN N<Activity>::WHERE(!EXISTS(_::{created_at})) {
+        activity::UPDATE({created_at: activity::{timestamp}, updated_at: activity::{timestamp}})
+    }
+    N<User>::WHERE(!EXISTS(_::{created_at}))::UPDATE({created_at: NOW, updated_at: NOW})
+    N<TimelineNode>::WHERE(!EXISTS(_::{created_at}))::UPDATE({created_at: NOW, updated_at: NOW})
+    N<Entity>::WHERE(!EXISTS(_::{created_at}))::UPDATE({created_at: NOW, updated_at: NOW})
+    N<Concept>::WHERE(!EXISTS(_::{created_at}))::UPDATE({created_at: NOW, updated_at: NOW})
+    N<WorkflowPattern>::WHERE(!EXISTS(_::{created_at}))::UPDATE({created_at: NOW, updated_at: NOW})
+    N<Block>::WHERE(!EXISTS(_::{created_at}))::UPDATE({created_at: NOW, updated_at: NOW})
+    N<Tool>::WHERE(!EXISTS(_::{created_at}))::UPDATE({created_at: NOW, updated_at: NOW})
+    existing_version <- N<SchemaVersion>
+    existing_version::UpsertN({version: 2, last_migration: "BackfillNodeTimestamps", updated_at: NOW})
+    RETURN "Success"
+
+// ============================================================================
+// DRY RUNS (read-only: count and sample what each data migration would touch)
+// ============================================================================
+
+QUERY BackfillPatternAndBlockOwnershipDryRun(sample_size: I64) =>
+    patterns <- N<WorkflowPattern>::WHERE(AND(_::{user_id}::NEQ(""), !EXISTS(_::In<UserHasPattern>)))
+    blocks <- N<Block>::WHERE(AND(_::{user_id}::NEQ(""), !EXISTS(_::In<UserHasBlock>)))
+    pattern_count <- patterns::COUNT
+    block_count <- blocks::COUNT
+    RETURN {
+        migration: "BackfillPatternAndBlockOwnership",
+        patterns: {count: pattern_count, sample: patterns::RANGE(0, sample_size)},
+        blocks: {count: block_count, sample: blocks::RANGE(0, sample_size)}
+    }
+
+QUERY BackfillSessionOwnershipDryRun(sample_size: I64) =>
+    sessions <- N<Session>::WHERE(AND(_::{user_key}::NEQ(""), !EXISTS(_::In<UserOwnsSession>)))
+    session_count <- sessions::COUNT
+    RETURN {
+        migration: "BackfillSessionOwnership",
+        sessions: {count: session_count, sample: sessions::RANGE(0, sample_size)}
+    }
+
+QUERY DeriveNodeContainsSessionEdgesDryRun(sample_size: I64) =>
+    sessions <- N<Session>::WHERE(_::Out<SessionInNode>::COUNT::GT(_::In<NodeContainsSession>::COUNT))
+    session_count <- sessions::COUNT
+    RETURN {
+        migration: "DeriveNodeContainsSessionEdges",
+        sessions: {count: session_count, sample: sessions::RANGE(0, sample_size)}
+    }
+
+QUERY ConsolidateRelatesToEdgesDryRun(sample_size: I64) =>
+    legacy <- E<RelatesTo>
+    edge_count <- legacy::COUNT
+    RETURN {
+        migration: "ConsolidateRelatesToEdges",
+        edges: {count: edge_count, sample: legacy::RANGE(0, sample_size)}
+    }
+
+QUERY BackfillActivityDaysDryRun(sample_size: I64) =>
+    activities <- N<Activity>::WHERE(_::{day}::EQ(""))
+    activity_count <- activities::COUNT
+    RETURN {
+        migration: "BackfillActivityDays",
+        activities: {count: activity_count, sample: activities::RANGE(0, sample_size)}
+    }
+
+QUERY BackfillNodeTimestampsDryRun() =>
+    users <- N<User>::WHERE(!EXISTS(_::{created_at}))::COUNT
+    nodes <- N<TimelineNode>::WHERE(!EXISTS(_::{created_at}))::COUNT
+    sessions <- N<Session>::WHERE(!EXISTS(_::{created_at}))::COUNT
+    activities <- N<Activity>::WHERE(!EXISTS(_::{created_at}))::COUNT
+    entities <- N<Entity>::WHERE(!EXISTS(_::{created_at}))::COUNT
+    concepts <- N<Concept>::WHERE(!EXISTS(_::{created_at}))::COUNT
+    patterns <- N<WorkflowPattern>::WHERE(!EXISTS(_::{created_at}))::COUNT
+    blocks <- N<Block>::WHERE(!EXISTS(_::{created_at}))::COUNT
+    tools <- N<Tool>::WHERE(!EXISTS(_::{created_at}))::COUNT
+    RETURN {
+        migration: "BackfillNodeTimestamps",
+        counts: {User: users, TimelineNode: nodes, Session: sessions, Activity: activities, Entity: entities, Concept: concepts, WorkflowPattern: patterns, Block: blocks, Tool: tools}
+    }
+
+// ============================================================================
+// MAINTENANCE
+// ============================================================================
+
+// Rewrite every indexed key in place so its secondary index entry is rebuilt
+QUERY RebuildSecondaryIndices() =>
+    FOR user IN N<User> {
+        user::UPDATE({external_id: user::{external_id}})
+    }
+    FOR node IN N<TimelineNode> {
+        node::UPDATE({external_id: node::{external_id}})
+    }
+    FOR session IN N<Session> {
+        session::UPDATE({external_id: session::{external_id}})
+    }
+    FOR activity IN N<Activity> {
+        activity::UPDATE({screenshot_external_id: activity::{screenshot_external_id}})
+    }
+    FOR entity IN N<Entity> {
+        entity::UPDATE({name: entity::{name}})
+    }
+    FOR concept IN N<Concept> {
+        concept::UPDATE({name: concept::{name}})
+    }
+    FOR pattern IN N<WorkflowPattern> {
+        pattern::UPDATE({intent_category: pattern::{intent_category}})
+    }
+    FOR block IN N<Block> {
+        block::UPDATE({canonical_slug: block::{canonical_slug}})
+    }
+    FOR step IN N<Step> {
+        step::UPDATE({step_id: step::{step_id}})
+    }
+    FOR tool IN N<Tool> {
+        tool::UPDATE({canonical_name: tool::{canonical_name}})
+    }
+    FOR job IN N<EmbeddingJob> {
+        job::UPDATE({job_id: job::{job_id}})
+    }
+    RETURN "Success"
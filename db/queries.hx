@@ -8,12 +8,18 @@
 // ============================================================================
 
 QUERY UpsertUser(external_id: String, metadata: String) =>
-    existing <- N<User>::WHERE(_::{external_id}::EQ(external_id))
-    user <- existing::UpsertN({external_id: external_id, metadata: metadata})
+    existing <- N<User>({external_id: external_id})
+    user <- existing::UpsertN({external_id: external_id, metadata: metadata, updated_at: NOW})
     RETURN user
 
+// Record whether a user appears in other users' GetSimilarUsers results
+QUERY SetUserCohortOptIn(external_id: String, cohort_opt_in: Boolean) =>
+    user <- N<User>({external_id: external_id})::UPDATE({cohort_opt_in: cohort_opt_in, updated_at: NOW})
+    RETURN user
+
+#[mcp]
 QUERY GetUserByExternalId(external_id: String) =>
-    user <- N<User>::WHERE(_::{external_id}::EQ(external_id))
+    user <- N<User>({external_id: external_id})
     RETURN user
 
 // ============================================================================
@@ -21,47 +27,153 @@ QUERY GetUserByExternalId(external_id: String) =>
 // ============================================================================
 
 QUERY UpsertTimelineNode(external_id: String, user_key: String, node_type: String, title: String, metadata: String) =>
-    existing <- N<TimelineNode>::WHERE(_::{external_id}::EQ(external_id))
-    node <- existing::UpsertN({external_id: external_id, node_type: node_type, title: title, metadata: metadata})
-    user <- N<User>::WHERE(_::{external_id}::EQ(user_key))
+    existing <- N<TimelineNode>({external_id: external_id})
+    node <- existing::UpsertN({external_id: external_id, node_type: node_type, title: title, metadata: metadata, updated_at: NOW})
+    user <- N<User>({external_id: user_key})
     AddE<UserOwnsNode>::From(user)::To(node)
     RETURN node
 
+#[mcp]
 QUERY GetTimelineNodesByUser(user_key: String) =>
-    nodes <- N<User>::WHERE(_::{external_id}::EQ(user_key))::Out<UserOwnsNode>
+    nodes <- N<User>({external_id: user_key})::Out<UserOwnsNode>
     RETURN nodes
 
 QUERY GetTimelineNodeByExternalId(external_id: String) =>
-    node <- N<TimelineNode>::WHERE(_::{external_id}::EQ(external_id))
+    node <- N<TimelineNode>({external_id: external_id})
     RETURN node
 
+// Write through HelixGraphService.linkNodeDependency, which rejects links that would close a cycle
+QUERY LinkNodeDependency(from_external_id: String, to_external_id: String, dependency_type: String) =>
+    from_node <- N<TimelineNode>({external_id: from_external_id})
+    to_node <- N<TimelineNode>({external_id: to_external_id})
+    existing <- from_node::OutE<DependsOn>::WHERE(_::ToN::{external_id}::EQ(to_external_id))
+    existing::UpsertE({dependency_type: dependency_type})::From(from_node)::To(to_node)
+    RETURN "Success"
+
+// Dependency tree below a timeline node, following DependsOn up to 3 levels deep
+QUERY GetDependencyTree(external_id: String) =>
+    node <- N<TimelineNode>({external_id: external_id})
+    RETURN node::{
+        external_id,
+        title,
+        node_type,
+        depends_on: _::OutE<DependsOn>::{
+            dependency_type,
+            node: _::ToN::{
+                external_id,
+                title,
+                node_type,
+                depends_on: _::OutE<DependsOn>::{
+                    dependency_type,
+                    node: _::ToN::{
+                        external_id,
+                        title,
+                        node_type,
+                        depends_on: _::OutE<DependsOn>::{dependency_type, node: _::ToN::{external_id, title, node_type}}
+                    }
+                }
+            }
+        }
+    }
+
+// Nodes that depend on a node, directly or through up to 3 DependsOn hops
+QUERY GetDependents(external_id: String) =>
+    direct <- N<TimelineNode>({external_id: external_id})::In<DependsOn>
+    second <- direct::In<DependsOn>
+    third <- second::In<DependsOn>
+    RETURN {
+        direct: direct,
+        transitive: {depth_2: second, depth_3: third}
+    }
+
+// Every DependsOn edge among a user's timeline nodes, for HelixGraphService's cycle detection and
+// execution order. HQL traversals have a fixed depth, so both run over the full edge list there
+#[mcp]
+QUERY GetDependencyEdges(user_key: String) =>
+    edges <- N<User>({external_id: user_key})::Out<UserOwnsNode>::OutE<DependsOn>
+    RETURN edges::{from: _::FromN::{external_id}, to: _::ToN::{external_id}, dependency_type}
+
 // ============================================================================
 // SESSION OPERATIONS
 // ============================================================================
 
 QUERY UpsertSession(external_id: String, user_key: String, node_key: String, start_time: Date, end_time: Date, duration_seconds: I64, screenshot_count: I64, workflow_primary: String, workflow_secondary: String, workflow_confidence: F64, metadata: String) =>
-    existing <- N<Session>::WHERE(_::{external_id}::EQ(external_id))
-    session <- existing::UpsertN({external_id: external_id, start_time: start_time, end_time: end_time, duration_seconds: duration_seconds, screenshot_count: screenshot_count, workflow_primary: workflow_primary, workflow_secondary: workflow_secondary, workflow_confidence: workflow_confidence, metadata: metadata})
-    user <- N<User>::WHERE(_::{external_id}::EQ(user_key))
+    existing <- N<Session>({external_id: external_id})
+    session <- existing::UpsertN({external_id: external_id, start_time: start_time, end_time: end_time, duration_seconds: duration_seconds, screenshot_count: screenshot_count, workflow_primary: workflow_primary, workflow_secondary: workflow_secondary, workflow_confidence: workflow_confidence, metadata: metadata, updated_at: NOW})
+    user <- N<User>({external_id: user_key})
     AddE<UserOwnsSession>::From(user)::To(session)
     RETURN session
 
+// Upsert a session and embed its summary server-side into SessionEmbedding
+QUERY UpsertSessionWithEmbedding(external_id: String, user_key: String, node_key: String, start_time: Date, end_time: Date, duration_seconds: I64, screenshot_count: I64, workflow_primary: String, workflow_secondary: String, workflow_confidence: F64, metadata: String, summary: String) =>
+    existing <- N<Session>({external_id: external_id})
+    session <- existing::UpsertN({external_id: external_id, start_time: start_time, end_time: end_time, duration_seconds: duration_seconds, screenshot_count: screenshot_count, workflow_primary: workflow_primary, workflow_secondary: workflow_secondary, workflow_confidence: workflow_confidence, metadata: metadata, updated_at: NOW})
+    user <- N<User>({external_id: user_key})
+    AddE<UserOwnsSession>::From(user)::To(session)
+    existing_vector <- session::Out<SessionHasEmbedding>
+    vector <- existing_vector::UpsertV(Embed(summary), {session_id: external_id})
+    existing_link <- session::OutE<SessionHasEmbedding>
+    existing_link::UpsertE({})::From(session)::To(vector)
+    RETURN session
+
 QUERY LinkSessionToNode(session_external_id: String, node_external_id: String) =>
-    session <- N<Session>::WHERE(_::{external_id}::EQ(session_external_id))
-    node <- N<TimelineNode>::WHERE(_::{external_id}::EQ(node_external_id))
+    session <- N<Session>({external_id: session_external_id})
+    node <- N<TimelineNode>({external_id: node_external_id})
     AddE<SessionInNode>::From(session)::To(node)
+    AddE<NodeContainsSession>::From(node)::To(session)
+    RETURN "Success"
+
+// Link a session to the same user's previous session with the idle gap between them
+QUERY LinkSessionFollows(previous_external_id: String, next_external_id: String, gap_seconds: I64, gap_bucket: String) =>
+    previous <- N<Session>({external_id: previous_external_id})
+    next <- N<Session>({external_id: next_external_id})
+    existing <- previous::OutE<Follows>::WHERE(_::ToN::{external_id}::EQ(next_external_id))
+    existing::UpsertE({gap_seconds: gap_seconds, gap_bucket: gap_bucket})::From(previous)::To(next)
     RETURN "Success"
 
+#[mcp]
 QUERY GetSessionsByUser(user_key: String, start: I64, end_range: I64) =>
-    sessions <- N<User>::WHERE(_::{external_id}::EQ(user_key))::Out<UserOwnsSession>::RANGE(start, end_range)
+    sessions <- N<User>({external_id: user_key})::Out<UserOwnsSession>::RANGE(start, end_range)
     RETURN sessions
 
 QUERY GetSessionsByNode(node_key: String) =>
-    sessions <- N<TimelineNode>::WHERE(_::{external_id}::EQ(node_key))::In<SessionInNode>
+    sessions <- N<TimelineNode>({external_id: node_key})::In<SessionInNode>
+    RETURN sessions
+
+QUERY GetSessionsContainedByNode(node_key: String) =>
+    sessions <- N<TimelineNode>({external_id: node_key})::Out<NodeContainsSession>
     RETURN sessions
 
+// Node drill-down in one request: the node's sessions, each with its activities
+QUERY GetActivitiesForTimelineNode(node_key: String) =>
+    sessions <- N<TimelineNode>({external_id: node_key})::Out<NodeContainsSession>::ORDER<Desc>(_::{start_time})
+    RETURN sessions::{external_id, start_time, end_time, workflow_primary, activities: _::In<ActivityInSession>::ORDER<Asc>(_::{timestamp})}
+
+// Session subgraph for the graphvis frontend, as {nodes, edges} keyed by label (same shape as GetConceptNeighborhood)
+QUERY GetSessionSubgraph(session_external_id: String) =>
+    session <- N<Session>({external_id: session_external_id})
+    activity_edges <- session::InE<ActivityInSession>
+    activities <- activity_edges::FromN
+    entity_edges <- activities::OutE<ActivityMentionsEntity>
+    concept_edges <- activities::OutE<ActivityRelatedToConcept>
+    switch_edges <- activities::OutE<SwitchesTo>::WHERE(EXISTS(_::ToN::Out<ActivityInSession>::WHERE(_::{external_id}::EQ(session_external_id))))
+    RETURN {
+        nodes: {
+            Session: session,
+            Activity: activities,
+            Entity: entity_edges::ToN::DEDUP,
+            Concept: concept_edges::ToN::DEDUP
+        },
+        edges: {
+            ActivityInSession: activity_edges,
+            ActivityMentionsEntity: entity_edges,
+            ActivityRelatedToConcept: concept_edges,
+            SwitchesTo: switch_edges
+        }
+    }
+
 QUERY GetRelatedSessions(session_external_id: String) =>
-    sessions <- N<Session>::WHERE(_::{external_id}::EQ(session_external_id))::Out<SessionInNode>::In<SessionInNode>
+    sessions <- N<Session>({external_id: session_external_id})::Out<SessionInNode>::In<SessionInNode>
     RETURN sessions
 
 // Get all sessions excluding a user (for peer analysis)
@@ -70,40 +182,85 @@ QUERY GetAllSessionsExcludingUser(exclude_user_key: String, start: I64, end_rang
     RETURN sessions
 
 // Aggregate sessions by workflow (returns full session data grouped by workflow_primary)
+#[mcp]
 QUERY AggregateSessionsByWorkflow(user_key: String) =>
-    sessions <- N<User>::WHERE(_::{external_id}::EQ(user_key))::Out<UserOwnsSession>
+    sessions <- N<User>({external_id: user_key})::Out<UserOwnsSession>
     RETURN sessions::AGGREGATE_BY(workflow_primary)
 
 // ============================================================================
 // ACTIVITY OPERATIONS
 // ============================================================================
 
-QUERY UpsertActivity(session_key: String, screenshot_external_id: String, workflow_tag: String, timestamp: Date, summary: String, confidence: F64, metadata: String) =>
-    existing <- N<Activity>::WHERE(_::{screenshot_external_id}::EQ(screenshot_external_id))
-    activity <- existing::UpsertN({screenshot_external_id: screenshot_external_id, workflow_tag: workflow_tag, timestamp: timestamp, summary: summary, confidence: confidence, metadata: metadata})
+QUERY UpsertActivity(session_key: String, screenshot_external_id: String, workflow_tag: String, timestamp: Date, day: String, summary: String, confidence: F64, metadata: String) =>
+    existing <- N<Activity>({screenshot_external_id: screenshot_external_id})
+    activity <- existing::UpsertN({screenshot_external_id: screenshot_external_id, workflow_tag: workflow_tag, timestamp: timestamp, day: day, summary: summary, confidence: confidence, metadata: metadata, updated_at: NOW})
+    RETURN activity
+
+// Upsert an activity and embed its summary server-side into ActivityEmbedding
+QUERY UpsertActivityWithEmbedding(session_key: String, screenshot_external_id: String, workflow_tag: String, timestamp: Date, day: String, summary: String, confidence: F64, metadata: String) =>
+    existing <- N<Activity>({screenshot_external_id: screenshot_external_id})
+    activity <- existing::UpsertN({screenshot_external_id: screenshot_external_id, workflow_tag: workflow_tag, timestamp: timestamp, day: day, summary: summary, confidence: confidence, metadata: metadata, updated_at: NOW})
+    existing_vector <- activity::Out<ActivityHasEmbedding>
+    vector <- existing_vector::UpsertV(Embed(summary), {screenshot_external_id: screenshot_external_id})
+    existing_link <- activity::OutE<ActivityHasEmbedding>
+    existing_link::UpsertE({})::From(activity)::To(vector)
     RETURN activity
 
 QUERY LinkActivityToSession(screenshot_external_id: String, session_external_id: String) =>
-    activity <- N<Activity>::WHERE(_::{screenshot_external_id}::EQ(screenshot_external_id))
-    session <- N<Session>::WHERE(_::{external_id}::EQ(session_external_id))
+    activity <- N<Activity>({screenshot_external_id: screenshot_external_id})
+    session <- N<Session>({external_id: session_external_id})
     AddE<ActivityInSession>::From(activity)::To(session)
     RETURN "Success"
 
+QUERY LinkActivitySwitch(from_screenshot_external_id: String, to_screenshot_external_id: String, switch_type: String) =>
+    from_activity <- N<Activity>({screenshot_external_id: from_screenshot_external_id})
+    to_activity <- N<Activity>({screenshot_external_id: to_screenshot_external_id})
+    existing <- from_activity::OutE<SwitchesTo>::WHERE(_::ToN::{screenshot_external_id}::EQ(to_screenshot_external_id))
+    existing::UpsertE({switch_type: switch_type})::From(from_activity)::To(to_activity)
+    RETURN "Success"
+
+// Context-switch chain starting at an activity, followed forward over SwitchesTo (at most 5 hops)
+QUERY GetActivitySwitchChain(screenshot_external_id: String) =>
+    start <- N<Activity>({screenshot_external_id: screenshot_external_id})
+    hop_1 <- start::OutE<SwitchesTo>
+    hop_2 <- hop_1::ToN::OutE<SwitchesTo>
+    hop_3 <- hop_2::ToN::OutE<SwitchesTo>
+    hop_4 <- hop_3::ToN::OutE<SwitchesTo>
+    hop_5 <- hop_4::ToN::OutE<SwitchesTo>
+    RETURN {
+        start: start,
+        hop_1: hop_1::{switch_type, activity: _::ToN},
+        hop_2: hop_2::{switch_type, activity: _::ToN},
+        hop_3: hop_3::{switch_type, activity: _::ToN},
+        hop_4: hop_4::{switch_type, activity: _::ToN},
+        hop_5: hop_5::{switch_type, activity: _::ToN}
+    }
+
 QUERY GetActivitiesBySession(session_key: String) =>
-    activities <- N<Session>::WHERE(_::{external_id}::EQ(session_key))::In<ActivityInSession>
+    activities <- N<Session>({external_id: session_key})::In<ActivityInSession>
     RETURN activities
 
+// Entities mentioned in a session, each returned once with its mention count in that session
+QUERY GetEntitiesForSession(session_external_id: String) =>
+    entities <- N<Session>({external_id: session_external_id})::In<ActivityInSession>::Out<ActivityMentionsEntity>::DEDUP
+    RETURN entities::{name, entity_type, occurrences: _::In<ActivityMentionsEntity>::WHERE(EXISTS(_::Out<ActivityInSession>::WHERE(_::{external_id}::EQ(session_external_id))))::COUNT}
+
+// A session's concepts, each returned once, ranked by the summed relevance of the session's activity links to it
+QUERY GetConceptsForSession(session_external_id: String, limit: I64) =>
+    concepts <- N<Session>({external_id: session_external_id})::In<ActivityInSession>::Out<ActivityRelatedToConcept>::DEDUP::ORDER<Desc>(SUM(_::InE<ActivityRelatedToConcept>::WHERE(EXISTS(_::FromN::Out<ActivityInSession>::WHERE(_::{external_id}::EQ(session_external_id))))::{relevance}))::RANGE(0, limit)
+    RETURN concepts::{name, category, relevance_score, relevance: SUM(_::InE<ActivityRelatedToConcept>::WHERE(EXISTS(_::FromN::Out<ActivityInSession>::WHERE(_::{external_id}::EQ(session_external_id))))::{relevance}), occurrences: _::InE<ActivityRelatedToConcept>::WHERE(EXISTS(_::FromN::Out<ActivityInSession>::WHERE(_::{external_id}::EQ(session_external_id))))::COUNT}
+
 // ============================================================================
 // ENTITY OPERATIONS
 // ============================================================================
 
 QUERY UpsertEntity(name: String, entity_type: String, metadata: String) =>
     existing <- N<Entity>({name: name})
-    entity <- existing::UpsertN({name: name, entity_type: entity_type, metadata: metadata})
+    entity <- existing::UpsertN({name: name, entity_type: entity_type, metadata: metadata, updated_at: NOW})
     RETURN entity
 
 QUERY LinkActivityToEntity(screenshot_external_id: String, entity_name: String, context: String) =>
-    activity <- N<Activity>::WHERE(_::{screenshot_external_id}::EQ(screenshot_external_id))
+    activity <- N<Activity>({screenshot_external_id: screenshot_external_id})
     entity <- N<Entity>({name: entity_name})
     AddE<ActivityMentionsEntity>::From(activity)::To(entity)
     RETURN "Success"
@@ -112,89 +269,939 @@ QUERY GetEntityOccurrences(entity_name: String) =>
     activities <- N<Entity>({name: entity_name})::In<ActivityMentionsEntity>
     RETURN activities
 
+// Autocomplete over entity names (substring match, alphabetical)
+QUERY SuggestEntities(prefix: String, limit: I64) =>
+    entities <- N<Entity>::WHERE(_::{name}::CONTAINS(prefix))::ORDER<Asc>(_::{name})::RANGE(0, limit)
+    RETURN entities::{name, entity_type}
+
 // ============================================================================
 // CONCEPT OPERATIONS
 // ============================================================================
 
 QUERY UpsertConcept(name: String, category: String, relevance_score: F64) =>
     existing <- N<Concept>({name: name})
-    concept <- existing::UpsertN({name: name, category: category, relevance_score: relevance_score})
+    concept <- existing::UpsertN({name: name, category: category, relevance_score: relevance_score, updated_at: NOW})
+    RETURN concept
+
+// Upsert a concept and embed its description server-side into ConceptEmbedding
+QUERY UpsertConceptWithEmbedding(name: String, category: String, relevance_score: F64, description: String) =>
+    existing <- N<Concept>({name: name})
+    concept <- existing::UpsertN({name: name, category: category, relevance_score: relevance_score, description: description, updated_at: NOW})
+    existing_vector <- concept::Out<ConceptHasEmbedding>
+    vector <- existing_vector::UpsertV(Embed(description), {concept_name: name, description: description})
+    existing_link <- concept::OutE<ConceptHasEmbedding>
+    existing_link::UpsertE({})::From(concept)::To(vector)
     RETURN concept
 
 QUERY LinkActivityToConcept(screenshot_external_id: String, concept_name: String, relevance: F64) =>
-    activity <- N<Activity>::WHERE(_::{screenshot_external_id}::EQ(screenshot_external_id))
+    activity <- N<Activity>({screenshot_external_id: screenshot_external_id})
     concept <- N<Concept>({name: concept_name})
     AddE<ActivityRelatedToConcept>::From(activity)::To(concept)
     RETURN "Success"
 
+QUERY LinkBlockToConcept(user_id: String, canonical_slug: String, concept_name: String, relevance: F64) =>
+    block <- N<User>({external_id: user_id})::Out<UserHasBlock>::WHERE(_::{canonical_slug}::EQ(canonical_slug))
+    concept <- N<Concept>({name: concept_name})
+    existing <- block::OutE<BlockRelatesConcept>::WHERE(_::ToN::{name}::EQ(concept_name))
+    existing::UpsertE({relevance: relevance})::From(block)::To(concept)
+    RETURN "Success"
+
+// Link many activity/concept pairs in one write; pairs that are already linked are updated in place
+QUERY BulkLinkActivityConcepts(links: [{screenshot_external_id: String, concept_name: String, relevance: F64}]) =>
+    FOR {screenshot_external_id, concept_name, relevance} IN links {
+        activity <- N<Activity>({screenshot_external_id: screenshot_external_id})
+        concept <- N<Concept>({name: concept_name})
+        existing <- activity::OutE<ActivityRelatedToConcept>::WHERE(_::ToN::{name}::EQ(concept_name))
+        existing::UpsertE({relevance: relevance})::From(activity)::To(concept)
+    }
+    RETURN "Success"
+
+// Upsert concepts together with their ConceptEmbedding vectors in one transaction
+QUERY BulkUpsertConceptsWithEmbeddings(concepts: [{name: String, category: String, relevance_score: F64, description: String, embedding: [F64]}]) =>
+    FOR {name, category, relevance_score, description, embedding} IN concepts {
+        existing <- N<Concept>({name: name})
+        concept <- existing::UpsertN({name: name, category: category, relevance_score: relevance_score, description: description, updated_at: NOW})
+        existing_vector <- concept::Out<ConceptHasEmbedding>
+        vector <- existing_vector::UpsertV(embedding, {concept_name: name, description: description})
+        existing_link <- concept::OutE<ConceptHasEmbedding>
+        existing_link::UpsertE({})::From(concept)::To(vector)
+    }
+    RETURN "Success"
+
+// Where a concept has shown up in a user's workflows
+#[mcp]
+QUERY GetBlocksForConcept(concept_name: String, user_id: String) =>
+    relations <- N<Concept>({name: concept_name})::InE<BlockRelatesConcept>::WHERE(EXISTS(_::FromN::In<UserHasBlock>::WHERE(_::{external_id}::EQ(user_id))))::ORDER<Desc>(_::{relevance})
+    RETURN relations::{relevance, block: _::FromN}
+
+// Connecting paths between two concepts through a user's own activities and sessions. The two path
+// shapes are matched with fixed-depth traversals, not a shortest-path search:
+//   via_activity (2 hops): from -[ActivityRelatedToConcept]- Activity -[ActivityRelatedToConcept]- to
+//   via_session  (4 hops): from -[ActivityRelatedToConcept]- Activity -[ActivityInSession]- Session
+//                              -[ActivityInSession]- Activity -[ActivityRelatedToConcept]- to
+// Every path carries its intermediate nodes and the edges between them, each edge with its label.
+// via_activity paths are the shorter ones, so via_session is only worth reading when via_activity is empty
+#[mcp]
+QUERY GetConceptPath(user_key: String, from_name: String, to_name: String, limit: I64) =>
+    from_concept <- N<Concept>({name: from_name})
+    from_activities <- from_concept::In<ActivityRelatedToConcept>::WHERE(EXISTS(_::Out<ActivityInSession>::In<UserOwnsSession>::WHERE(_::{external_id}::EQ(user_key))))
+    via_activity <- from_activities::WHERE(EXISTS(_::Out<ActivityRelatedToConcept>::WHERE(_::{name}::EQ(to_name))))::RANGE(0, limit)
+    via_session <- from_activities::Out<ActivityInSession>::DEDUP::WHERE(EXISTS(_::In<ActivityInSession>::Out<ActivityRelatedToConcept>::WHERE(_::{name}::EQ(to_name))))::RANGE(0, limit)
+    RETURN {
+        via_activity: via_activity::{
+            activity: _::{screenshot_external_id, timestamp, summary},
+            from_edge: _::OutE<ActivityRelatedToConcept>::WHERE(_::ToN::{name}::EQ(from_name))::{label, from: _::FromN::{screenshot_external_id}, to: _::ToN::{name}},
+            to_edge: _::OutE<ActivityRelatedToConcept>::WHERE(_::ToN::{name}::EQ(to_name))::{label, from: _::FromN::{screenshot_external_id}, to: _::ToN::{name}}
+        },
+        via_session: via_session::{
+            session: _::{external_id, start_time, workflow_primary},
+            from_activities: _::InE<ActivityInSession>::WHERE(EXISTS(_::FromN::Out<ActivityRelatedToConcept>::WHERE(_::{name}::EQ(from_name))))::{
+                session_edge: _::{label, from: _::FromN::{screenshot_external_id}, to: _::ToN::{external_id}},
+                concept_edge: _::FromN::OutE<ActivityRelatedToConcept>::WHERE(_::ToN::{name}::EQ(from_name))::{label, from: _::FromN::{screenshot_external_id}, to: _::ToN::{name}},
+                activity: _::FromN::{screenshot_external_id, summary}
+            },
+            to_activities: _::InE<ActivityInSession>::WHERE(EXISTS(_::FromN::Out<ActivityRelatedToConcept>::WHERE(_::{name}::EQ(to_name))))::{
+                session_edge: _::{label, from: _::FromN::{screenshot_external_id}, to: _::ToN::{external_id}},
+                concept_edge: _::FromN::OutE<ActivityRelatedToConcept>::WHERE(_::ToN::{name}::EQ(to_name))::{label, from: _::FromN::{screenshot_external_id}, to: _::ToN::{name}},
+                activity: _::FromN::{screenshot_external_id, summary}
+            }
+        }
+    }
+
+// Graph-visualization payload around a concept, limited to the user's own activities and blocks, as
+// {nodes, edges} keyed by label. HQL hop counts are fixed, so k is 2 here (adds the activities'
+// sessions) and 1 in GetConceptNeighborhoodShallow. node_limit caps the total node count, concept
+// included: activities take at most half of the rest (each adds at most one session), and blocks fill
+// whatever activities and sessions leave
+#[mcp]
+QUERY GetConceptNeighborhood(user_key: String, name: String, node_limit: I64) =>
+    concept <- N<Concept>({name: name})
+    activity_edges <- concept::InE<ActivityRelatedToConcept>::WHERE(EXISTS(_::FromN::Out<ActivityInSession>::In<UserOwnsSession>::WHERE(_::{external_id}::EQ(user_key))))::RANGE(0, DIV(SUB(node_limit, 1), 2))
+    activities <- activity_edges::FromN
+    session_edges <- activities::OutE<ActivityInSession>
+    sessions <- session_edges::ToN::DEDUP
+    block_edges <- concept::InE<BlockRelatesConcept>::WHERE(EXISTS(_::FromN::In<UserHasBlock>::WHERE(_::{external_id}::EQ(user_key))))::RANGE(0, SUB(SUB(node_limit, 1), ADD(activities::COUNT, sessions::COUNT)))
+    blocks <- block_edges::FromN
+    RETURN {
+        nodes: {
+            Concept: concept,
+            Activity: activities,
+            Block: blocks,
+            Session: sessions
+        },
+        edges: {
+            ActivityRelatedToConcept: activity_edges,
+            BlockRelatesConcept: block_edges,
+            ActivityInSession: session_edges
+        }
+    }
+
+// Depth-1 variant of GetConceptNeighborhood: the concept with its activities and blocks only, with the
+// same total node_limit (activities take at most half of it, blocks fill the rest)
+#[mcp]
+QUERY GetConceptNeighborhoodShallow(user_key: String, name: String, node_limit: I64) =>
+    concept <- N<Concept>({name: name})
+    activity_edges <- concept::InE<ActivityRelatedToConcept>::WHERE(EXISTS(_::FromN::Out<ActivityInSession>::In<UserOwnsSession>::WHERE(_::{external_id}::EQ(user_key))))::RANGE(0, DIV(SUB(node_limit, 1), 2))
+    block_edges <- concept::InE<BlockRelatesConcept>::WHERE(EXISTS(_::FromN::In<UserHasBlock>::WHERE(_::{external_id}::EQ(user_key))))::RANGE(0, SUB(SUB(node_limit, 1), activity_edges::COUNT))
+    RETURN {
+        nodes: {
+            Concept: concept,
+            Activity: activity_edges::FromN,
+            Block: block_edges::FromN
+        },
+        edges: {
+            ActivityRelatedToConcept: activity_edges,
+            BlockRelatesConcept: block_edges
+        }
+    }
+
 QUERY GetConceptsByCategory(category: String) =>
     concepts <- N<Concept>::WHERE(_::{category}::EQ(category))
     RETURN concepts
 
+// ============================================================================
+// BUNDLE INGESTION
+// ============================================================================
+
+// Ingest a full session (activities, entities, concepts, node link) in a single write transaction.
+// Safe to re-run: every edge is upserted, and concept relevance stays on the per-activity edge
+QUERY IngestSessionBundle(external_id: String, user_key: String, node_key: String, start_time: Date, end_time: Date, duration_seconds: I64, screenshot_count: I64, workflow_primary: String, workflow_secondary: String, workflow_confidence: F64, metadata: String, activities: [{screenshot_external_id: String, workflow_tag: String, timestamp: Date, day: String, summary: String, confidence: F64, metadata: String}], entities: [{screenshot_external_id: String, name: String, entity_type: String, context: String, metadata: String}], concepts: [{screenshot_external_id: String, name: String, category: String, relevance: F64}]) =>
+    existing <- N<Session>({external_id: external_id})
+    session <- existing::UpsertN({external_id: external_id, start_time: start_time, end_time: end_time, duration_seconds: duration_seconds, screenshot_count: screenshot_count, workflow_primary: workflow_primary, workflow_secondary: workflow_secondary, workflow_confidence: workflow_confidence, metadata: metadata, updated_at: NOW})
+    user <- N<User>({external_id: user_key})
+    existing_owner <- user::OutE<UserOwnsSession>::WHERE(_::ToN::{external_id}::EQ(external_id))
+    existing_owner::UpsertE({})::From(user)::To(session)
+    node <- N<TimelineNode>({external_id: node_key})
+    existing_in_node <- session::OutE<SessionInNode>::WHERE(_::ToN::{external_id}::EQ(node_key))
+    existing_in_node::UpsertE({})::From(session)::To(node)
+    existing_contains <- node::OutE<NodeContainsSession>::WHERE(_::ToN::{external_id}::EQ(external_id))
+    existing_contains::UpsertE({})::From(node)::To(session)
+    FOR {screenshot_external_id, workflow_tag, timestamp, day, summary, confidence, metadata} IN activities {
+        existing_activity <- N<Activity>({screenshot_external_id: screenshot_external_id})
+        activity <- existing_activity::UpsertN({screenshot_external_id: screenshot_external_id, workflow_tag: workflow_tag, timestamp: timestamp, day: day, summary: summary, confidence: confidence, metadata: metadata, updated_at: NOW})
+        existing_in_session <- activity::OutE<ActivityInSession>::WHERE(_::ToN::{external_id}::EQ(external_id))
+        existing_in_session::UpsertE({})::From(activity)::To(session)
+    }
+    FOR {screenshot_external_id, name, entity_type, context, metadata} IN entities {
+        existing_entity <- N<Entity>({name: name})
+        entity <- existing_entity::UpsertN({name: name, entity_type: entity_type, metadata: metadata, updated_at: NOW})
+        mentioning <- N<Activity>({screenshot_external_id: screenshot_external_id})
+        existing_mention <- mentioning::OutE<ActivityMentionsEntity>::WHERE(_::ToN::{name}::EQ(name))
+        existing_mention::UpsertE({context: context})::From(mentioning)::To(entity)
+    }
+    FOR {screenshot_external_id, name, category, relevance} IN concepts {
+        existing_concept <- N<Concept>({name: name})
+        concept <- existing_concept::UpsertN({name: name, category: category, updated_at: NOW})
+        related <- N<Activity>({screenshot_external_id: screenshot_external_id})
+        existing_link <- related::OutE<ActivityRelatedToConcept>::WHERE(_::ToN::{name}::EQ(name))
+        existing_link::UpsertE({relevance: relevance})::From(related)::To(concept)
+    }
+    RETURN session
+
 // ============================================================================
 // CROSS-SESSION CONTEXT (Graph Traversals)
 // ============================================================================
 
 // Get entities via: User -> Sessions -> Activities -> Entities
+#[mcp]
 QUERY GetCrossSessionContext(user_key: String) =>
-    entities <- N<User>::WHERE(_::{external_id}::EQ(user_key))::Out<UserOwnsSession>::In<ActivityInSession>::Out<ActivityMentionsEntity>
+    entities <- N<User>({external_id: user_key})::Out<UserOwnsSession>::In<ActivityInSession>::Out<ActivityMentionsEntity>
     RETURN entities
 
 // Get concepts via: User -> Sessions -> Activities -> Concepts
+#[mcp]
 QUERY GetCrossSessionConcepts(user_key: String) =>
-    concepts <- N<User>::WHERE(_::{external_id}::EQ(user_key))::Out<UserOwnsSession>::In<ActivityInSession>::Out<ActivityRelatedToConcept>
+    concepts <- N<User>({external_id: user_key})::Out<UserOwnsSession>::In<ActivityInSession>::Out<ActivityRelatedToConcept>
     RETURN concepts
 
 // ============================================================================
 // WORKFLOW PATTERN OPERATIONS
 // ============================================================================
 
+// Patterns are keyed by (user_id, intent_category): each user owns their own pattern per category
 QUERY UpsertWorkflowPattern(user_id: String, intent_category: String, occurrence_count: I64, metadata: String) =>
-    existing <- N<WorkflowPattern>::WHERE(_::{intent_category}::EQ(intent_category))
-    pattern <- existing::UpsertN({intent_category: intent_category, occurrence_count: occurrence_count, metadata: metadata})
-    user <- N<User>::WHERE(_::{external_id}::EQ(user_id))
-    AddE<UserHasPattern>::From(user)::To(pattern)
+    user <- N<User>({external_id: user_id})
+    existing <- user::Out<UserHasPattern>::WHERE(_::{intent_category}::EQ(intent_category))
+    pattern <- existing::UpsertN({user_id: user_id, intent_category: intent_category, occurrence_count: occurrence_count, metadata: metadata, updated_at: NOW})
+    ownership <- user::OutE<UserHasPattern>::WHERE(_::ToN::{intent_category}::EQ(intent_category))
+    ownership::UpsertE({})::From(user)::To(pattern)
     RETURN pattern
 
+#[mcp]
 QUERY GetWorkflowPatterns(user_id: String) =>
-    patterns <- N<User>::WHERE(_::{external_id}::EQ(user_id))::Out<UserHasPattern>
+    patterns <- N<User>({external_id: user_id})::Out<UserHasPattern>
     RETURN patterns
 
 QUERY GetPatternsByIntent(intent_category: String) =>
-    patterns <- N<WorkflowPattern>::WHERE(_::{intent_category}::EQ(intent_category))
+    patterns <- N<WorkflowPattern>({intent_category: intent_category})
     RETURN patterns
 
+// Blocks of a pattern in pipeline order (PatternContainsBlock.order)
+#[mcp]
+QUERY GetBlocksByPattern(user_id: String, intent_category: String) =>
+    links <- N<User>({external_id: user_id})::Out<UserHasPattern>::WHERE(_::{intent_category}::EQ(intent_category))::OutE<PatternContainsBlock>::ORDER<Asc>(_::{order})
+    RETURN links::{order, block: _::ToN}
+
+// Sessions a pattern occurred in, most recent first
+#[mcp]
+QUERY GetSessionsForPattern(user_id: String, intent_category: String) =>
+    occurrences <- N<User>({external_id: user_id})::Out<UserHasPattern>::WHERE(_::{intent_category}::EQ(intent_category))::OutE<PatternOccursInSession>::ORDER<Desc>(_::{occurred_at})
+    RETURN occurrences::{occurred_at, session: _::ToN}
+
+QUERY LinkPatternToBlock(user_id: String, intent_category: String, canonical_slug: String, order: I64) =>
+    user <- N<User>({external_id: user_id})
+    pattern <- user::Out<UserHasPattern>::WHERE(_::{intent_category}::EQ(intent_category))
+    block <- user::Out<UserHasBlock>::WHERE(_::{canonical_slug}::EQ(canonical_slug))
+    existing <- pattern::OutE<PatternContainsBlock>::WHERE(_::ToN::{canonical_slug}::EQ(canonical_slug))
+    existing::UpsertE({order: order})::From(pattern)::To(block)
+    RETURN "Success"
+
+QUERY LinkPatternToSession(user_id: String, intent_category: String, session_external_id: String, occurred_at: Date, week: String) =>
+    pattern <- N<User>({external_id: user_id})::Out<UserHasPattern>::WHERE(_::{intent_category}::EQ(intent_category))
+    session <- N<Session>({external_id: session_external_id})
+    existing <- pattern::OutE<PatternOccursInSession>::WHERE(_::ToN::{external_id}::EQ(session_external_id))
+    existing::UpsertE({occurred_at: occurred_at, week: week})::From(pattern)::To(session)
+    RETURN "Success"
+
 // ============================================================================
 // BLOCK OPERATIONS
 // ============================================================================
 
+// Blocks are keyed by (user_id, canonical_slug): each user owns their own block per slug
 QUERY UpsertBlock(user_id: String, canonical_slug: String, intent_label: String, primary_tool: String, occurrence_count: I64, metadata: String) =>
-    existing <- N<Block>::WHERE(_::{canonical_slug}::EQ(canonical_slug))
-    block <- existing::UpsertN({canonical_slug: canonical_slug, intent_label: intent_label, primary_tool: primary_tool, occurrence_count: occurrence_count, metadata: metadata})
-    user <- N<User>::WHERE(_::{external_id}::EQ(user_id))
-    AddE<UserHasBlock>::From(user)::To(block)
+    user <- N<User>({external_id: user_id})
+    existing <- user::Out<UserHasBlock>::WHERE(_::{canonical_slug}::EQ(canonical_slug))
+    block <- existing::UpsertN({user_id: user_id, canonical_slug: canonical_slug, intent_label: intent_label, primary_tool: primary_tool, occurrence_count: occurrence_count, metadata: metadata, updated_at: NOW})
+    ownership <- user::OutE<UserHasBlock>::WHERE(_::ToN::{canonical_slug}::EQ(canonical_slug))
+    ownership::UpsertE({})::From(user)::To(block)
     RETURN block
 
+#[mcp]
 QUERY GetBlocksByUser(user_id: String) =>
-    blocks <- N<User>::WHERE(_::{external_id}::EQ(user_id))::Out<UserHasBlock>
+    blocks <- N<User>({external_id: user_id})::Out<UserHasBlock>
     RETURN blocks
 
+// Write a user's mined block transition graph in one transaction; existing transitions are refreshed
+QUERY LinkBlockSequences(user_id: String, edges: [{from_slug: String, to_slug: String, frequency: I64, probability: F64}]) =>
+    user <- N<User>({external_id: user_id})
+    FOR {from_slug, to_slug, frequency, probability} IN edges {
+        from_block <- user::Out<UserHasBlock>::WHERE(_::{canonical_slug}::EQ(from_slug))
+        to_block <- user::Out<UserHasBlock>::WHERE(_::{canonical_slug}::EQ(to_slug))
+        existing <- from_block::OutE<NextBlock>::WHERE(_::ToN::{canonical_slug}::EQ(to_slug))
+        existing::UpsertE({frequency: frequency, probability: probability})::From(from_block)::To(to_block)
+    }
+    RETURN "Success"
+
+// Rebuild a user's NextBlock graph from their patterns in one write transaction. Each block in a
+// pattern chain is paired with the next one in PatternContainsBlock order (gaps in order are fine),
+// adding the pattern's occurrence_count to that transition's frequency. Transitions below
+// min_support are pruned, then probability is set to each survivor's share of its source block's
+// outgoing frequency, so a block's probabilities sum to 1 (MUL by 1.0 makes the division F64, not I64)
+QUERY MineBlockSequences(user_id: String, min_support: I64) =>
+    user <- N<User>({external_id: user_id})
+    DROP user::Out<UserHasBlock>::OutE<NextBlock>
+    FOR pattern IN user::Out<UserHasPattern> {
+        last_order <- MAX(pattern::OutE<PatternContainsBlock>::{order})
+        FOR link IN pattern::OutE<PatternContainsBlock>::WHERE(_::{order}::LT(last_order)) {
+            from_block <- link::ToN
+            to_block <- pattern::OutE<PatternContainsBlock>::WHERE(_::{order}::GT(link::{order}))::ORDER<Asc>(_::{order})::RANGE(0, 1)::ToN
+            existing <- from_block::OutE<NextBlock>::WHERE(_::ToN::{canonical_slug}::EQ(to_block::{canonical_slug}))
+            transition <- existing::UpsertE({probability: 0.0})::From(from_block)::To(to_block)
+            transition::UPDATE({frequency: ADD(transition::{frequency}, pattern::{occurrence_count})})
+        }
+    }
+    DROP user::Out<UserHasBlock>::OutE<NextBlock>::WHERE(_::{frequency}::LT(min_support))
+    FOR block IN user::Out<UserHasBlock>::WHERE(EXISTS(_::OutE<NextBlock>)) {
+        total <- SUM(block::OutE<NextBlock>::{frequency})
+        FOR transition IN block::OutE<NextBlock> {
+            transition::UPDATE({probability: DIV(MUL(transition::{frequency}, 1.0), total)})
+        }
+    }
+    RETURN "Success"
+
+QUERY LinkBlockToTool(user_id: String, canonical_slug: String, canonical_name: String, is_primary: Boolean, frequency: I64) =>
+    block <- N<User>({external_id: user_id})::Out<UserHasBlock>::WHERE(_::{canonical_slug}::EQ(canonical_slug))
+    tool <- N<Tool>({canonical_name: canonical_name})
+    existing <- block::OutE<BlockUsesTool>::WHERE(_::ToN::{canonical_name}::EQ(canonical_name))
+    existing::UpsertE({is_primary: is_primary, frequency: frequency})::From(block)::To(tool)
+    RETURN "Success"
+
+// Tools a block uses, most frequently used first
+#[mcp]
+QUERY GetToolsForBlock(user_id: String, canonical_slug: String) =>
+    usages <- N<User>({external_id: user_id})::Out<UserHasBlock>::WHERE(_::{canonical_slug}::EQ(canonical_slug))::OutE<BlockUsesTool>::ORDER<Desc>(_::{frequency})
+    RETURN usages::{is_primary, frequency, tool: _::ToN}
+
+// Concepts related to a block, most relevant first
+#[mcp]
+QUERY GetConceptsForBlock(user_id: String, canonical_slug: String) =>
+    relations <- N<User>({external_id: user_id})::Out<UserHasBlock>::WHERE(_::{canonical_slug}::EQ(canonical_slug))::OutE<BlockRelatesConcept>::ORDER<Desc>(_::{relevance})
+    RETURN relations::{relevance, concept: _::ToN}
+
+// Full NextBlock adjacency for a user's blocks, as [{from_slug, to_slug, frequency, probability}]
+#[mcp]
+QUERY GetBlockTransitionMatrix(user_id: String) =>
+    transitions <- N<User>({external_id: user_id})::Out<UserHasBlock>::OutE<NextBlock>
+    RETURN transitions::{from_slug: _::FromN::{canonical_slug}, to_slug: _::ToN::{canonical_slug}, frequency, probability}
+
+// Shortest NextBlock path between two of a user's blocks (e.g. "research" to "publish"),
+// returned as the ordered blocks and transitions along it
+#[mcp]
+QUERY GetBlockPath(user_id: String, from_slug: String, to_slug: String) =>
+    blocks <- N<User>({external_id: user_id})::Out<UserHasBlock>
+    from_block <- blocks::WHERE(_::{canonical_slug}::EQ(from_slug))
+    to_block <- blocks::WHERE(_::{canonical_slug}::EQ(to_slug))
+    path <- from_block::ShortestPath<NextBlock>::To(to_block)
+    RETURN path
+
+// A user's core blocks by weighted in-degree (sum of incoming NextBlock frequency), for
+// "your core workflow steps"
+#[mcp]
+QUERY GetBlockImportance(user_id: String, limit: I64) =>
+    blocks <- N<User>({external_id: user_id})::Out<UserHasBlock>::ORDER<Desc>(SUM(_::InE<NextBlock>::{frequency}))::RANGE(0, limit)
+    RETURN blocks::{
+        canonical_slug,
+        intent_label,
+        primary_tool,
+        occurrence_count,
+        importance: SUM(_::InE<NextBlock>::{frequency}),
+        in_degree: _::In<NextBlock>::COUNT
+    }
+
+// ============================================================================
+// STEP OPERATIONS
+// ============================================================================
+
+QUERY UpsertStep(step_id: String, session_id: String, action_type: String, description: String, app_context: String, timestamp: Date, confidence: F64, metadata: String) =>
+    existing <- N<Step>({step_id: step_id})
+    step <- existing::UpsertN({step_id: step_id, session_id: session_id, action_type: action_type, description: description, app_context: app_context, timestamp: timestamp, confidence: confidence, metadata: metadata, updated_at: NOW})
+    RETURN step
+
+QUERY LinkBlockToStep(user_id: String, canonical_slug: String, step_id: String, order: I64) =>
+    block <- N<User>({external_id: user_id})::Out<UserHasBlock>::WHERE(_::{canonical_slug}::EQ(canonical_slug))
+    step <- N<Step>({step_id: step_id})
+    existing <- block::OutE<BlockContainsStep>::WHERE(_::ToN::{step_id}::EQ(step_id))
+    existing::UpsertE({order: order})::From(block)::To(step)
+    RETURN "Success"
+
+QUERY LinkStepToActivity(step_id: String, screenshot_external_id: String, screenshot_id: I64) =>
+    step <- N<Step>({step_id: step_id})
+    activity <- N<Activity>({screenshot_external_id: screenshot_external_id})
+    existing <- step::OutE<StepEvidencedBy>::WHERE(_::ToN::{screenshot_external_id}::EQ(screenshot_external_id))
+    existing::UpsertE({screenshot_id: screenshot_id})::From(step)::To(activity)
+    RETURN "Success"
+
+// Evidence screenshots for a step (linked activities plus the screenshot_id edge property)
+QUERY GetEvidenceForStep(step_id: String) =>
+    evidence <- N<Step>({step_id: step_id})::OutE<StepEvidencedBy>
+    RETURN evidence::{screenshot_id, activity: _::ToN}
+
+// A user's steps (reached through their evidence activities) in timestamp order, for
+// HelixGraphService.mineStepSequences
+#[mcp]
+QUERY GetStepsByUser(user_key: String) =>
+    steps <- N<User>({external_id: user_key})::Out<UserOwnsSession>::In<ActivityInSession>::In<StepEvidencedBy>::DEDUP::ORDER<Asc>(_::{timestamp})
+    RETURN steps::{step_id, session_id, action_type, timestamp}
+
 // ============================================================================
 // TOOL OPERATIONS
 // ============================================================================
 
 QUERY UpsertTool(canonical_name: String, category: String, metadata: String) =>
     existing <- N<Tool>({canonical_name: canonical_name})
-    tool <- existing::UpsertN({canonical_name: canonical_name, category: category, metadata: metadata})
+    tool <- existing::UpsertN({canonical_name: canonical_name, category: category, metadata: metadata, updated_at: NOW})
     RETURN tool
 
+// ============================================================================
+// EMBEDDING OPERATIONS (precomputed vectors)
+// ============================================================================
+
+// Vectors must be output of helix.toml's embedding_model, the one model for every vector label;
+// HelixGraphService rejects lengths other than its EMBEDDING_DIMENSIONS
+
+QUERY UpsertSessionEmbedding(session_external_id: String, embedding: [F64]) =>
+    session <- N<Session>({external_id: session_external_id})
+    existing_vector <- session::Out<SessionHasEmbedding>
+    vector <- existing_vector::UpsertV(embedding, {session_id: session_external_id})
+    existing_link <- session::OutE<SessionHasEmbedding>
+    existing_link::UpsertE({})::From(session)::To(vector)
+    RETURN vector
+
+QUERY UpsertActivityEmbedding(screenshot_external_id: String, embedding: [F64]) =>
+    activity <- N<Activity>({screenshot_external_id: screenshot_external_id})
+    existing_vector <- activity::Out<ActivityHasEmbedding>
+    vector <- existing_vector::UpsertV(embedding, {screenshot_external_id: screenshot_external_id})
+    existing_link <- activity::OutE<ActivityHasEmbedding>
+    existing_link::UpsertE({})::From(activity)::To(vector)
+    RETURN vector
+
+// Also stores the description on the Concept node, where SearchConceptsFullText indexes it
+QUERY UpsertConceptEmbedding(concept_name: String, description: String, embedding: [F64]) =>
+    concept <- N<Concept>({name: concept_name})::UPDATE({description: description, updated_at: NOW})
+    existing_vector <- concept::Out<ConceptHasEmbedding>
+    vector <- existing_vector::UpsertV(embedding, {concept_name: concept_name, description: description})
+    existing_link <- concept::OutE<ConceptHasEmbedding>
+    existing_link::UpsertE({})::From(concept)::To(vector)
+    RETURN vector
+
+// ============================================================================
+// VECTOR SEARCH
+// ============================================================================
+
+// Search results carry the source Session/Activity/Concept node alongside the vector score
+
+// User-scoped searches filter after the HNSW search, so they search a top_k candidate pool
+// (sized for the share of the index one user owns) and then keep `limit` hits. Every pool is
+// MIN(MAX(top_k, limit), 1000): never smaller than limit, and capped so one call cannot scan the index
+
+// min_score cuts off low-quality matches server-side; pass 0.0 to keep every hit
+QUERY SearchSimilarActivities(user_key: String, query_embedding: [F64], limit: I64, top_k: I64, min_score: F64) =>
+    results <- SearchV<ActivityEmbedding>(query_embedding, MIN(MAX(top_k, limit), 1000))::WHERE(AND(_::{score}::GTE(min_score), EXISTS(_::In<ActivityHasEmbedding>::Out<ActivityInSession>::In<UserOwnsSession>::WHERE(_::{external_id}::EQ(user_key)))))::RANGE(0, limit)
+    RETURN results::{screenshot_external_id, score, activity: _::In<ActivityHasEmbedding>}
+
+// Restrict a user's activity search to a single workflow_tag
+QUERY SearchSimilarActivitiesByWorkflowTag(user_key: String, query_embedding: [F64], workflow_tag: String, limit: I64, top_k: I64, min_score: F64) =>
+    results <- SearchV<ActivityEmbedding>(query_embedding, MIN(MAX(top_k, limit), 1000))::WHERE(AND(_::{score}::GTE(min_score), EXISTS(_::In<ActivityHasEmbedding>::WHERE(AND(_::{workflow_tag}::EQ(workflow_tag), EXISTS(_::Out<ActivityInSession>::In<UserOwnsSession>::WHERE(_::{external_id}::EQ(user_key))))))))::RANGE(0, limit)
+    RETURN results::{screenshot_external_id, score, activity: _::In<ActivityHasEmbedding>}
+
+// Restrict activity search to the activities of a single session owned by user_key
+QUERY SearchSimilarActivitiesInSession(user_key: String, query_embedding: [F64], session_key: String, limit: I64, top_k: I64, min_score: F64) =>
+    results <- SearchV<ActivityEmbedding>(query_embedding, MIN(MAX(top_k, limit), 1000))::WHERE(AND(_::{score}::GTE(min_score), EXISTS(_::In<ActivityHasEmbedding>::Out<ActivityInSession>::WHERE(AND(_::{external_id}::EQ(session_key), EXISTS(_::In<UserOwnsSession>::WHERE(_::{external_id}::EQ(user_key))))))))::RANGE(0, limit)
+    RETURN results::{screenshot_external_id, score, activity: _::In<ActivityHasEmbedding>}
+
+QUERY SearchSimilarConcepts(query_embedding: [F64], limit: I64, min_score: F64) =>
+    results <- SearchV<ConceptEmbedding>(query_embedding, limit)::WHERE(_::{score}::GTE(min_score))
+    RETURN results::{concept_name, description, score, concept: _::In<ConceptHasEmbedding>}
+
+// Restrict concept search to concepts in a single category (filtered from a top_k candidate pool)
+QUERY SearchSimilarConceptsInCategory(query_embedding: [F64], category: String, limit: I64, top_k: I64, min_score: F64) =>
+    results <- SearchV<ConceptEmbedding>(query_embedding, MIN(MAX(top_k, limit), 1000))::WHERE(AND(_::{score}::GTE(min_score), EXISTS(_::In<ConceptHasEmbedding>::WHERE(_::{category}::EQ(category)))))::RANGE(0, limit)
+    RETURN results::{concept_name, description, score, concept: _::In<ConceptHasEmbedding>}
+
+// Only returns embeddings of sessions owned by user_key
+QUERY SearchSimilarSessions(user_key: String, query_embedding: [F64], limit: I64, top_k: I64, min_score: F64) =>
+    results <- SearchV<SessionEmbedding>(query_embedding, MIN(MAX(top_k, limit), 1000))::WHERE(AND(_::{score}::GTE(min_score), EXISTS(_::In<SessionHasEmbedding>::In<UserOwnsSession>::WHERE(_::{external_id}::EQ(user_key)))))::RANGE(0, limit)
+    RETURN results::{session_id, score, session: _::In<SessionHasEmbedding>}
+
+// MMR-reranked SearchSimilarSessions over a top_k candidate pool
+// lambda trades relevance (1.0) for diversity (0.0) and is clamped to [0.0, 1.0]; distance is "cosine",
+// "euclidean" or "dotproduct" (checked by HelixGraphService.searchSimilarSessionsDiverse)
+QUERY SearchSimilarSessionsDiverse(user_key: String, query_embedding: [F64], limit: I64, top_k: I64, lambda: F64, distance: String) =>
+    results <- SearchV<SessionEmbedding>(query_embedding, MIN(MAX(top_k, limit), 1000))::WHERE(EXISTS(_::In<SessionHasEmbedding>::In<UserOwnsSession>::WHERE(_::{external_id}::EQ(user_key))))::RerankMMR(lambda: MIN(MAX(lambda, 0.0), 1.0), distance: distance)::RANGE(0, limit)
+    RETURN results::{session_id, score, session: _::In<SessionHasEmbedding>}
+
+// Exact (non-HNSW) variant of SearchSimilarSessions for small or freshly-built datasets. Scores only
+// the user's own session embeddings, so it needs no candidate pool or owner filter
+QUERY BruteForceSearchSimilarSessions(user_key: String, query_embedding: [F64], limit: I64, min_score: F64) =>
+    results <- N<User>({external_id: user_key})::Out<UserOwnsSession>::Out<SessionHasEmbedding>::BruteForceSearchV(query_embedding, limit)::WHERE(_::{score}::GTE(min_score))
+    RETURN results::{session_id, score, session: _::In<SessionHasEmbedding>}
+
+// k nearest of the user's sessions to a session's stored embedding, excluding the session itself
+#[mcp]
+QUERY GetSimilarSessionsToSession(user_key: String, session_external_id: String, limit: I64, top_k: I64) =>
+    source <- N<Session>({external_id: session_external_id})::Out<SessionHasEmbedding>
+    results <- SearchV<SessionEmbedding>(source, MIN(MAX(top_k, limit), 1000))::WHERE(AND(_::{session_id}::NEQ(session_external_id), EXISTS(_::In<SessionHasEmbedding>::In<UserOwnsSession>::WHERE(_::{external_id}::EQ(user_key)))))::RANGE(0, limit)
+    RETURN results::{session_id, score, session: _::In<SessionHasEmbedding>}
+
+// k nearest of the user's activities to an activity's stored embedding, excluding the activity itself
+#[mcp]
+QUERY GetSimilarActivitiesToActivity(user_key: String, screenshot_external_id: String, limit: I64, top_k: I64) =>
+    source <- N<Activity>({screenshot_external_id: screenshot_external_id})::Out<ActivityHasEmbedding>
+    results <- SearchV<ActivityEmbedding>(source, MIN(MAX(top_k, limit), 1000))::WHERE(AND(_::{screenshot_external_id}::NEQ(screenshot_external_id), EXISTS(_::In<ActivityHasEmbedding>::Out<ActivityInSession>::In<UserOwnsSession>::WHERE(_::{external_id}::EQ(user_key)))))::RANGE(0, limit)
+    RETURN results::{screenshot_external_id, score, activity: _::In<ActivityHasEmbedding>}
+
+// Embed the query text server-side and search the user's activity embeddings
+QUERY SearchActivitiesByText(user_key: String, query: String, limit: I64, top_k: I64) =>
+    results <- SearchV<ActivityEmbedding>(Embed(query), MIN(MAX(top_k, limit), 1000))::WHERE(EXISTS(_::In<ActivityHasEmbedding>::Out<ActivityInSession>::In<UserOwnsSession>::WHERE(_::{external_id}::EQ(user_key))))::RANGE(0, limit)
+    RETURN results::{screenshot_external_id, score, activity: _::In<ActivityHasEmbedding>}
+
+// Embed the query text server-side and return the user's matching Session nodes
+QUERY SearchSessionsByText(user_key: String, query: String, limit: I64, top_k: I64) =>
+    sessions <- SearchV<SessionEmbedding>(Embed(query), MIN(MAX(top_k, limit), 1000))::In<SessionHasEmbedding>::WHERE(EXISTS(_::In<UserOwnsSession>::WHERE(_::{external_id}::EQ(user_key))))::RANGE(0, limit)
+    RETURN sessions
+
+// Embed the query text server-side and return the matching Concept nodes
+QUERY SearchConceptsByText(query: String, limit: I64) =>
+    concepts <- SearchV<ConceptEmbedding>(Embed(query), limit)::In<ConceptHasEmbedding>
+    RETURN concepts
+
+// Same as SearchConceptsByText, keeping only concepts in the given category (filtered from a top_k candidate pool)
+QUERY SearchConceptsByTextInCategory(query: String, category: String, limit: I64, top_k: I64) =>
+    concepts <- SearchV<ConceptEmbedding>(Embed(query), MIN(MAX(top_k, limit), 1000))::In<ConceptHasEmbedding>::WHERE(_::{category}::EQ(category))::RANGE(0, limit)
+    RETURN concepts
+
+// Universal search: the user's sessions and activities plus concepts for one query, as three
+// score-ordered lists keyed by source label. HQL cannot merge results of different labels, so
+// HelixGraphService.searchEverything fuses them into one ranking
+QUERY SearchEverything(user_key: String, query: String, limit: I64, top_k: I64) =>
+    sessions <- SearchV<SessionEmbedding>(Embed(query), MIN(MAX(top_k, limit), 1000))::WHERE(EXISTS(_::In<SessionHasEmbedding>::In<UserOwnsSession>::WHERE(_::{external_id}::EQ(user_key))))::RANGE(0, limit)
+    activities <- SearchV<ActivityEmbedding>(Embed(query), MIN(MAX(top_k, limit), 1000))::WHERE(EXISTS(_::In<ActivityHasEmbedding>::Out<ActivityInSession>::In<UserOwnsSession>::WHERE(_::{external_id}::EQ(user_key))))::RANGE(0, limit)
+    concepts <- SearchV<ConceptEmbedding>(Embed(query), limit)
+    RETURN {
+        Session: sessions::{session_id, score, session: _::In<SessionHasEmbedding>},
+        Activity: activities::{screenshot_external_id, score, activity: _::In<ActivityHasEmbedding>},
+        Concept: concepts::{concept_name, description, score, concept: _::In<ConceptHasEmbedding>}
+    }
+
+// ============================================================================
+// FULL-TEXT SEARCH (BM25)
+// ============================================================================
+
+// Keyword search over session workflow_primary/workflow_secondary/metadata, limited to user_key's sessions
+// (filtered from a top_k candidate pool)
+QUERY SearchSessionsFullText(user_key: String, query: String, limit: I64, top_k: I64) =>
+    sessions <- SearchBM25<Session>(query, MIN(MAX(top_k, limit), 1000))::WHERE(EXISTS(_::In<UserOwnsSession>::WHERE(_::{external_id}::EQ(user_key))))::RANGE(0, limit)
+    RETURN sessions
+
+// Keyword search over concept names and descriptions
+QUERY SearchConceptsFullText(query: String, limit: I64) =>
+    concepts <- SearchBM25<Concept>(query, limit)
+    RETURN concepts
+
+// ============================================================================
+// HYBRID SEARCH
+// ============================================================================
+
+// Keyword and semantic search over sessions owned by user_key, returned as two ranked lists.
+// Semantic scoring runs only over the user's own session embeddings; BM25 has no candidate
+// restriction, so it searches a top_k pool and filters to the user's sessions
+QUERY HybridSearchUserSessions(user_key: String, query: String, limit: I64, top_k: I64) =>
+    candidates <- N<User>({external_id: user_key})::Out<UserOwnsSession>::Out<SessionHasEmbedding>
+    keyword <- SearchBM25<Session>(query, MIN(MAX(top_k, limit), 1000))::WHERE(EXISTS(_::In<UserOwnsSession>::WHERE(_::{external_id}::EQ(user_key))))::RANGE(0, limit)
+    semantic <- candidates::BruteForceSearchV(Embed(query), limit)
+    RETURN {
+        keyword: keyword,
+        semantic: semantic::{session_id, score, session: _::In<SessionHasEmbedding>}
+    }
+
+// Concept suggestions for reviewing one of a user's sessions, scored against the session's stored
+// embedding. graph ranks the concepts of sessions sharing its timeline node; semantic ranks the nearest
+// concepts overall from a top_k pool. Concepts already on the session are excluded from both.
+// HelixGraphService.recommendConceptsForSession fuses the two lists into one ranking
+#[mcp]
+QUERY RecommendConceptsForSession(user_key: String, session_external_id: String, limit: I64, top_k: I64) =>
+    session <- N<User>({external_id: user_key})::Out<UserOwnsSession>::WHERE(_::{external_id}::EQ(session_external_id))
+    session_vector <- session::Out<SessionHasEmbedding>
+    candidates <- session::Out<SessionInNode>::In<SessionInNode>::In<ActivityInSession>::Out<ActivityRelatedToConcept>::DEDUP::WHERE(!EXISTS(_::In<ActivityRelatedToConcept>::Out<ActivityInSession>::WHERE(_::{external_id}::EQ(session_external_id))))::Out<ConceptHasEmbedding>
+    graph <- candidates::BruteForceSearchV(session_vector, limit)
+    semantic <- SearchV<ConceptEmbedding>(session_vector, MIN(MAX(top_k, limit), 1000))::WHERE(!EXISTS(_::In<ConceptHasEmbedding>::In<ActivityRelatedToConcept>::Out<ActivityInSession>::WHERE(_::{external_id}::EQ(session_external_id))))::RANGE(0, limit)
+    RETURN {
+        graph: graph::{concept_name, description, score, concept: _::In<ConceptHasEmbedding>},
+        semantic: semantic::{concept_name, description, score, concept: _::In<ConceptHasEmbedding>}
+    }
+
+// ============================================================================
+// EMBEDDING JOBS
+// ============================================================================
+
+// Bulk re-embeds run in a client-side worker, not in the database: the worker registers the job here,
+// processes it in short EmbedSessionsBatch/EmbedActivitiesBatch/EmbedConceptsBatch calls and reports
+// progress through UpdateEmbeddingJobProgress. Re-registering a job_id leaves its progress untouched
+QUERY EnqueueEmbeddingJob(job_id: String, target_label: String, total: I64) =>
+    existing <- N<EmbeddingJob>({job_id: job_id})
+    job <- existing::UpsertN({job_id: job_id, target_label: target_label, total: total, updated_at: NOW})
+    RETURN job
+
+QUERY UpdateEmbeddingJobProgress(job_id: String, status: String, processed: I64, error: String) =>
+    job <- N<EmbeddingJob>({job_id: job_id})::UPDATE({status: status, processed: processed, error: error, updated_at: NOW})
+    RETURN job
+
+QUERY GetEmbeddingJobStatus(job_id: String) =>
+    job <- N<EmbeddingJob>({job_id: job_id})
+    RETURN job
+
+QUERY EmbedSessionsBatch(sessions: [{external_id: String, summary: String}]) =>
+    FOR {external_id, summary} IN sessions {
+        session <- N<Session>({external_id: external_id})
+        existing_vector <- session::Out<SessionHasEmbedding>
+        vector <- existing_vector::UpsertV(Embed(summary), {session_id: external_id})
+        existing_link <- session::OutE<SessionHasEmbedding>
+        existing_link::UpsertE({})::From(session)::To(vector)
+    }
+    RETURN "Success"
+
+QUERY EmbedActivitiesBatch(screenshot_external_ids: [String]) =>
+    FOR screenshot_external_id IN screenshot_external_ids {
+        activity <- N<Activity>({screenshot_external_id: screenshot_external_id})
+        existing_vector <- activity::Out<ActivityHasEmbedding>
+        vector <- existing_vector::UpsertV(Embed(activity::{summary}), {screenshot_external_id: screenshot_external_id})
+        existing_link <- activity::OutE<ActivityHasEmbedding>
+        existing_link::UpsertE({})::From(activity)::To(vector)
+    }
+    RETURN "Success"
+
+// Re-embed concept descriptions with the configured model, e.g. after embedding_model changes
+QUERY EmbedConceptsBatch(names: [String]) =>
+    FOR name IN names {
+        concept <- N<Concept>({name: name})
+        existing_vector <- concept::Out<ConceptHasEmbedding>
+        vector <- existing_vector::UpsertV(Embed(concept::{description}), {concept_name: name, description: concept::{description}})
+        existing_link <- concept::OutE<ConceptHasEmbedding>
+        existing_link::UpsertE({})::From(concept)::To(vector)
+    }
+    RETURN "Success"
+
+// ============================================================================
+// ANALYTICS (server-side aggregations)
+// ============================================================================
+
+// Activity counts per workflow_tag for a user, as [{workflow_tag, count}]
+#[mcp]
+QUERY GetWorkflowTagDistribution(user_key: String) =>
+    activities <- N<User>({external_id: user_key})::Out<UserOwnsSession>::In<ActivityInSession>
+    distribution <- activities::GROUP_BY(workflow_tag)
+    RETURN distribution
+
+// A user's most frequent concepts (number of the user's activities related to each)
+#[mcp]
+QUERY GetTopConceptsForUser(user_key: String, limit: I64) =>
+    concepts <- N<User>({external_id: user_key})::Out<UserOwnsSession>::In<ActivityInSession>::Out<ActivityRelatedToConcept>::DEDUP::ORDER<Desc>(_::In<ActivityRelatedToConcept>::WHERE(EXISTS(_::Out<ActivityInSession>::In<UserOwnsSession>::WHERE(_::{external_id}::EQ(user_key))))::COUNT)::RANGE(0, limit)
+    RETURN concepts::{name, category, occurrences: _::In<ActivityRelatedToConcept>::WHERE(EXISTS(_::Out<ActivityInSession>::In<UserOwnsSession>::WHERE(_::{external_id}::EQ(user_key))))::COUNT}
+
+// Activity counts per UTC day (Activity.day) within [start_date, end_date], for the heat-map
+// Activities written before Activity.day existed fall under "" until BackfillActivityDays has run
+#[mcp]
+QUERY GetDailyActivityCounts(user_key: String, start_date: Date, end_date: Date) =>
+    activities <- N<User>({external_id: user_key})::Out<UserOwnsSession>::In<ActivityInSession>::WHERE(AND(_::{timestamp}::GTE(start_date), _::{timestamp}::LTE(end_date)))
+    counts <- activities::GROUP_BY(day)
+    RETURN counts
+
+// avg/median/p95 of duration_seconds over a user's sessions (percentiles read off the sorted list)
+#[mcp]
+QUERY GetSessionDurationStats(user_key: String) =>
+    sessions <- N<User>({external_id: user_key})::Out<UserOwnsSession>::ORDER<Asc>(_::{duration_seconds})
+    count <- sessions::COUNT
+    average <- AVG(sessions::{duration_seconds})
+    median <- sessions::RANGE(DIV(count, 2), ADD(DIV(count, 2), 1))
+    p95 <- sessions::RANGE(DIV(MUL(count, 95), 100), ADD(DIV(MUL(count, 95), 100), 1))
+    RETURN {
+        count: count,
+        avg_seconds: average,
+        median_seconds: median::{duration_seconds},
+        p95_seconds: p95::{duration_seconds}
+    }
+
+// Same as GetSessionDurationStats, restricted to one workflow_primary
+#[mcp]
+QUERY GetSessionDurationStatsForWorkflow(user_key: String, workflow_primary: String) =>
+    sessions <- N<User>({external_id: user_key})::Out<UserOwnsSession>::WHERE(_::{workflow_primary}::EQ(workflow_primary))::ORDER<Asc>(_::{duration_seconds})
+    count <- sessions::COUNT
+    average <- AVG(sessions::{duration_seconds})
+    median <- sessions::RANGE(DIV(count, 2), ADD(DIV(count, 2), 1))
+    p95 <- sessions::RANGE(DIV(MUL(count, 95), 100), ADD(DIV(MUL(count, 95), 100), 1))
+    RETURN {
+        count: count,
+        avg_seconds: average,
+        median_seconds: median::{duration_seconds},
+        p95_seconds: p95::{duration_seconds}
+    }
+
+// For up to `limit` of a user's entities, the other entities mentioned in the same sessions.
+// shared_sessions is the number of the user's sessions in which both entities are mentioned
+#[mcp]
+QUERY GetEntityCooccurrence(user_key: String, limit: I64) =>
+    sessions <- N<User>({external_id: user_key})::Out<UserOwnsSession>
+    entities <- sessions::In<ActivityInSession>::Out<ActivityMentionsEntity>::DEDUP::RANGE(0, limit)
+    RETURN entities::|entity|{
+        name,
+        cooccurring: entity::In<ActivityMentionsEntity>::Out<ActivityInSession>::WHERE(EXISTS(_::In<UserOwnsSession>::WHERE(_::{external_id}::EQ(user_key))))::DEDUP::In<ActivityInSession>::Out<ActivityMentionsEntity>::DEDUP::WHERE(_::{name}::NEQ(entity::{name}))::{
+            name,
+            shared_sessions: _::In<ActivityMentionsEntity>::Out<ActivityInSession>::DEDUP::WHERE(AND(EXISTS(_::In<UserOwnsSession>::WHERE(_::{external_id}::EQ(user_key))), EXISTS(_::In<ActivityInSession>::Out<ActivityMentionsEntity>::WHERE(_::{name}::EQ(entity::{name})))))::COUNT
+        }
+    }
+
+// Concept counterpart of GetEntityCooccurrence: the other concepts related to activities of the same
+// sessions, with shared_sessions counting the user's sessions in which both concepts appear
+#[mcp]
+QUERY GetConceptCooccurrence(user_key: String, limit: I64) =>
+    sessions <- N<User>({external_id: user_key})::Out<UserOwnsSession>
+    concepts <- sessions::In<ActivityInSession>::Out<ActivityRelatedToConcept>::DEDUP::RANGE(0, limit)
+    RETURN concepts::|concept|{
+        name,
+        cooccurring: concept::In<ActivityRelatedToConcept>::Out<ActivityInSession>::WHERE(EXISTS(_::In<UserOwnsSession>::WHERE(_::{external_id}::EQ(user_key))))::DEDUP::In<ActivityInSession>::Out<ActivityRelatedToConcept>::DEDUP::WHERE(_::{name}::NEQ(concept::{name}))::{
+            name,
+            shared_sessions: _::In<ActivityRelatedToConcept>::Out<ActivityInSession>::DEDUP::WHERE(AND(EXISTS(_::In<UserOwnsSession>::WHERE(_::{external_id}::EQ(user_key))), EXISTS(_::In<ActivityInSession>::Out<ActivityRelatedToConcept>::WHERE(_::{name}::EQ(concept::{name})))))::COUNT
+        }
+    }
+
+// Weekly occurrence counts per intent_category for a user's patterns, as {intent_category, weeks: [{week, count}]}
+#[mcp]
+QUERY GetWorkflowPatternTrends(user_id: String, start_date: Date, end_date: Date) =>
+    patterns <- N<User>({external_id: user_id})::Out<UserHasPattern>
+    RETURN patterns::{
+        intent_category,
+        weeks: _::OutE<PatternOccursInSession>::WHERE(AND(_::{occurred_at}::GTE(start_date), _::{occurred_at}::LTE(end_date)))::GROUP_BY(week)
+    }
+
+// A user's timeline nodes ranked by linked session count, for the "most active projects" widget
+#[mcp]
+QUERY GetMostActiveTimelineNodes(user_key: String, limit: I64) =>
+    nodes <- N<User>({external_id: user_key})::Out<UserOwnsNode>::ORDER<Desc>(_::Out<NodeContainsSession>::COUNT)::RANGE(0, limit)
+    RETURN nodes::{
+        external_id,
+        title,
+        node_type,
+        session_count: _::Out<NodeContainsSession>::COUNT,
+        total_duration_seconds: SUM(_::Out<NodeContainsSession>::{duration_seconds})
+    }
+
+// Total screenshot_count over a user's sessions, plus each session's workflow_primary and count.
+// HQL cannot sum inside a group, so HelixGraphService.getScreenshotTotals builds the per-workflow sums
+#[mcp]
+QUERY GetScreenshotTotals(user_key: String) =>
+    sessions <- N<User>({external_id: user_key})::Out<UserOwnsSession>
+    total <- SUM(sessions::{screenshot_count})
+    RETURN {
+        total: total,
+        sessions: sessions::{workflow_primary, screenshot_count}
+    }
+
+// Same as GetScreenshotTotals, restricted to sessions starting within [start_date, end_date]
+#[mcp]
+QUERY GetScreenshotTotalsInRange(user_key: String, start_date: Date, end_date: Date) =>
+    sessions <- N<User>({external_id: user_key})::Out<UserOwnsSession>::WHERE(AND(_::{start_time}::GTE(start_date), _::{start_time}::LTE(end_date)))
+    total <- SUM(sessions::{screenshot_count})
+    RETURN {
+        total: total,
+        sessions: sessions::{workflow_primary, screenshot_count}
+    }
+
+// Activity confidence per workflow_tag for a user, to spot tags where the classifier is weak. HQL
+// cannot average inside a group, so HelixGraphService.getAverageConfidenceByWorkflowTag takes the means
+#[mcp]
+QUERY GetAverageConfidenceByWorkflowTag(user_key: String) =>
+    activities <- N<User>({external_id: user_key})::Out<UserOwnsSession>::In<ActivityInSession>
+    overall <- AVG(activities::{confidence})
+    RETURN {
+        overall_confidence: overall,
+        activities: activities::{workflow_tag, confidence}
+    }
+
+// Idle gaps between a user's consecutive sessions (Follows.gap_seconds): avg, max and bucket histogram
+#[mcp]
+QUERY GetIdleGapStats(user_key: String) =>
+    gaps <- N<User>({external_id: user_key})::Out<UserOwnsSession>::OutE<Follows>
+    count <- gaps::COUNT
+    average <- AVG(gaps::{gap_seconds})
+    longest <- gaps::ORDER<Desc>(_::{gap_seconds})::RANGE(0, 1)
+    histogram <- gaps::GROUP_BY(gap_bucket)
+    RETURN {
+        count: count,
+        avg_seconds: average,
+        max_seconds: longest::{gap_seconds},
+        histogram: histogram
+    }
+
+// One week's rollup for the weekly email digest, in a single read transaction.
+// Tools are those of blocks whose steps are evidenced by the week's activities.
+// Workflows come back as the full GROUP_BY (one row per workflow_primary); callers keep the top 5
+#[mcp]
+QUERY GetWeeklyWorkflowSummary(user_key: String, week_start: Date, week_end: Date) =>
+    sessions <- N<User>({external_id: user_key})::Out<UserOwnsSession>::WHERE(AND(_::{start_time}::GTE(week_start), _::{start_time}::LT(week_end)))
+    activities <- sessions::In<ActivityInSession>
+    session_count <- sessions::COUNT
+    total_duration <- SUM(sessions::{duration_seconds})
+    workflows <- sessions::GROUP_BY(workflow_primary)
+    tools <- activities::In<StepEvidencedBy>::In<BlockContainsStep>::Out<BlockUsesTool>::DEDUP::ORDER<Desc>(_::In<BlockUsesTool>::Out<BlockContainsStep>::Out<StepEvidencedBy>::WHERE(AND(_::{timestamp}::GTE(week_start), _::{timestamp}::LT(week_end), EXISTS(_::Out<ActivityInSession>::In<UserOwnsSession>::WHERE(_::{external_id}::EQ(user_key)))))::COUNT)::RANGE(0, 5)
+    concepts <- activities::Out<ActivityRelatedToConcept>::DEDUP::ORDER<Desc>(_::In<ActivityRelatedToConcept>::WHERE(AND(_::{timestamp}::GTE(week_start), _::{timestamp}::LT(week_end), EXISTS(_::Out<ActivityInSession>::In<UserOwnsSession>::WHERE(_::{external_id}::EQ(user_key)))))::COUNT)::RANGE(0, 5)
+    RETURN {
+        session_count: session_count,
+        total_duration_seconds: total_duration,
+        workflows: workflows,
+        top_tools: tools::{canonical_name, category},
+        top_concepts: concepts::{name, category}
+    }
+
+// Cross-user WorkflowPattern statistics for product analytics: per intent_category, how many distinct
+// users have the pattern and the average occurrence_count. Owner ids are only used for DEDUP inside
+// the query and are never returned. Not exposed over MCP
+QUERY GetAnonymousPatternStats() =>
+    patterns <- N<WorkflowPattern>::WHERE(EXISTS(_::In<UserHasPattern>))::{intent_category, occurrence_count}
+    user_count <- N<User>::WHERE(EXISTS(_::Out<UserHasPattern>))::COUNT
+    average <- AVG(patterns::{occurrence_count})
+    users_per_category <- E<UserHasPattern>::{intent_category: _::ToN::{intent_category}, owner: _::FromN::{external_id}}::DEDUP::GROUP_BY(intent_category)
+    by_category <- patterns::AGGREGATE_BY(intent_category)
+    RETURN {
+        user_count: user_count,
+        avg_occurrence_count: average,
+        users_per_category: users_per_category,
+        by_category: by_category
+    }
+
+// A user's top sessions by duration_seconds starting within [start_date, end_date]
+#[mcp]
+QUERY GetLongestSessions(user_key: String, start_date: Date, end_date: Date, limit: I64) =>
+    sessions <- N<User>({external_id: user_key})::Out<UserOwnsSession>::WHERE(AND(_::{start_time}::GTE(start_date), _::{start_time}::LTE(end_date)))::ORDER<Desc>(_::{duration_seconds})::RANGE(0, limit)
+    RETURN sessions::{external_id, start_time, end_time, duration_seconds, workflow_primary, node: _::Out<SessionInNode>::{external_id, title}}
+
+// Opted-in users most similar to a user, for cohort analysis. Candidates share at least one concept or
+// entity; they are ranked by the Jaccard index of their combined concept and entity sets,
+// shared / (own + theirs - shared). Only internal node ids leave the database. Reads other users'
+// data, so it is not exposed over MCP
+QUERY GetSimilarUsers(user_key: String, limit: I64) =>
+    activities <- N<User>({external_id: user_key})::Out<UserOwnsSession>::In<ActivityInSession>
+    own_concepts <- activities::Out<ActivityRelatedToConcept>::DEDUP::COUNT
+    own_entities <- activities::Out<ActivityMentionsEntity>::DEDUP::COUNT
+    users <- N<User>::WHERE(AND(AND(_::{cohort_opt_in}::EQ(true), _::{external_id}::NEQ(user_key)), OR(EXISTS(_::Out<UserOwnsSession>::In<ActivityInSession>::Out<ActivityRelatedToConcept>::In<ActivityRelatedToConcept>::Out<ActivityInSession>::In<UserOwnsSession>::WHERE(_::{external_id}::EQ(user_key))), EXISTS(_::Out<UserOwnsSession>::In<ActivityInSession>::Out<ActivityMentionsEntity>::In<ActivityMentionsEntity>::Out<ActivityInSession>::In<UserOwnsSession>::WHERE(_::{external_id}::EQ(user_key))))))::ORDER<Desc>(DIV(MUL(ADD(_::Out<UserOwnsSession>::In<ActivityInSession>::Out<ActivityRelatedToConcept>::DEDUP::WHERE(EXISTS(_::In<ActivityRelatedToConcept>::Out<ActivityInSession>::In<UserOwnsSession>::WHERE(_::{external_id}::EQ(user_key))))::COUNT, _::Out<UserOwnsSession>::In<ActivityInSession>::Out<ActivityMentionsEntity>::DEDUP::WHERE(EXISTS(_::In<ActivityMentionsEntity>::Out<ActivityInSession>::In<UserOwnsSession>::WHERE(_::{external_id}::EQ(user_key))))::COUNT), 1.0), SUB(ADD(ADD(own_concepts, own_entities), ADD(_::Out<UserOwnsSession>::In<ActivityInSession>::Out<ActivityRelatedToConcept>::DEDUP::COUNT, _::Out<UserOwnsSession>::In<ActivityInSession>::Out<ActivityMentionsEntity>::DEDUP::COUNT)), ADD(_::Out<UserOwnsSession>::In<ActivityInSession>::Out<ActivityRelatedToConcept>::DEDUP::WHERE(EXISTS(_::In<ActivityRelatedToConcept>::Out<ActivityInSession>::In<UserOwnsSession>::WHERE(_::{external_id}::EQ(user_key))))::COUNT, _::Out<UserOwnsSession>::In<ActivityInSession>::Out<ActivityMentionsEntity>::DEDUP::WHERE(EXISTS(_::In<ActivityMentionsEntity>::Out<ActivityInSession>::In<UserOwnsSession>::WHERE(_::{external_id}::EQ(user_key))))::COUNT))))::RANGE(0, limit)
+    RETURN users::{
+        id,
+        jaccard: DIV(MUL(ADD(_::Out<UserOwnsSession>::In<ActivityInSession>::Out<ActivityRelatedToConcept>::DEDUP::WHERE(EXISTS(_::In<ActivityRelatedToConcept>::Out<ActivityInSession>::In<UserOwnsSession>::WHERE(_::{external_id}::EQ(user_key))))::COUNT, _::Out<UserOwnsSession>::In<ActivityInSession>::Out<ActivityMentionsEntity>::DEDUP::WHERE(EXISTS(_::In<ActivityMentionsEntity>::Out<ActivityInSession>::In<UserOwnsSession>::WHERE(_::{external_id}::EQ(user_key))))::COUNT), 1.0), SUB(ADD(ADD(own_concepts, own_entities), ADD(_::Out<UserOwnsSession>::In<ActivityInSession>::Out<ActivityRelatedToConcept>::DEDUP::COUNT, _::Out<UserOwnsSession>::In<ActivityInSession>::Out<ActivityMentionsEntity>::DEDUP::COUNT)), ADD(_::Out<UserOwnsSession>::In<ActivityInSession>::Out<ActivityRelatedToConcept>::DEDUP::WHERE(EXISTS(_::In<ActivityRelatedToConcept>::Out<ActivityInSession>::In<UserOwnsSession>::WHERE(_::{external_id}::EQ(user_key))))::COUNT, _::Out<UserOwnsSession>::In<ActivityInSession>::Out<ActivityMentionsEntity>::DEDUP::WHERE(EXISTS(_::In<ActivityMentionsEntity>::Out<ActivityInSession>::In<UserOwnsSession>::WHERE(_::{external_id}::EQ(user_key))))::COUNT))),
+        shared_concepts: _::Out<UserOwnsSession>::In<ActivityInSession>::Out<ActivityRelatedToConcept>::DEDUP::WHERE(EXISTS(_::In<ActivityRelatedToConcept>::Out<ActivityInSession>::In<UserOwnsSession>::WHERE(_::{external_id}::EQ(user_key))))::COUNT,
+        shared_entities: _::Out<UserOwnsSession>::In<ActivityInSession>::Out<ActivityMentionsEntity>::DEDUP::WHERE(EXISTS(_::In<ActivityMentionsEntity>::Out<ActivityInSession>::In<UserOwnsSession>::WHERE(_::{external_id}::EQ(user_key))))::COUNT
+    }
+
 // ============================================================================
 // STATISTICS & BACKFILL
 // ============================================================================
 
-QUERY GetActivityCount() =>
-    count <- N<Activity>::COUNT
-    RETURN count
+// Node, vector and edge counts for every label in schema.hx, in one transaction. The single admin
+// count endpoint (capacity planning, sanity checks, backfill progress); the per-user counterpart is
+// GetUserGraphStats. Keep in sync with schema.hx when labels are added
+QUERY GetGraphStats() =>
+    n_User <- N<User>::COUNT
+    n_TimelineNode <- N<TimelineNode>::COUNT
+    n_Session <- N<Session>::COUNT
+    n_Activity <- N<Activity>::COUNT
+    n_Entity <- N<Entity>::COUNT
+    n_Concept <- N<Concept>::COUNT
+    n_WorkflowPattern <- N<WorkflowPattern>::COUNT
+    n_Block <- N<Block>::COUNT
+    n_Step <- N<Step>::COUNT
+    n_Tool <- N<Tool>::COUNT
+    n_SchemaVersion <- N<SchemaVersion>::COUNT
+    n_EmbeddingJob <- N<EmbeddingJob>::COUNT
+    v_SessionEmbedding <- V<SessionEmbedding>::COUNT
+    v_ActivityEmbedding <- V<ActivityEmbedding>::COUNT
+    v_ConceptEmbedding <- V<ConceptEmbedding>::COUNT
+    e_UserOwnsNode <- E<UserOwnsNode>::COUNT
+    e_DependsOn <- E<DependsOn>::COUNT
+    e_UserOwnsSession <- E<UserOwnsSession>::COUNT
+    e_SessionInNode <- E<SessionInNode>::COUNT
+    e_NodeContainsSession <- E<NodeContainsSession>::COUNT
+    e_Follows <- E<Follows>::COUNT
+    e_ActivityInSession <- E<ActivityInSession>::COUNT
+    e_SwitchesTo <- E<SwitchesTo>::COUNT
+    e_ActivityMentionsEntity <- E<ActivityMentionsEntity>::COUNT
+    e_ActivityRelatedToConcept <- E<ActivityRelatedToConcept>::COUNT
+    e_BlockUsesTool <- E<BlockUsesTool>::COUNT
+    e_BlockRelatesConcept <- E<BlockRelatesConcept>::COUNT
+    e_RelatesTo <- E<RelatesTo>::COUNT
+    e_UserHasPattern <- E<UserHasPattern>::COUNT
+    e_UserHasBlock <- E<UserHasBlock>::COUNT
+    e_BlockContainsStep <- E<BlockContainsStep>::COUNT
+    e_StepEvidencedBy <- E<StepEvidencedBy>::COUNT
+    e_PatternContainsBlock <- E<PatternContainsBlock>::COUNT
+    e_PatternOccursInSession <- E<PatternOccursInSession>::COUNT
+    e_NextBlock <- E<NextBlock>::COUNT
+    e_ConceptHasEmbedding <- E<ConceptHasEmbedding>::COUNT
+    e_SessionHasEmbedding <- E<SessionHasEmbedding>::COUNT
+    e_ActivityHasEmbedding <- E<ActivityHasEmbedding>::COUNT
+    RETURN {
+        nodes: {
+            User: n_User,
+            TimelineNode: n_TimelineNode,
+            Session: n_Session,
+            Activity: n_Activity,
+            Entity: n_Entity,
+            Concept: n_Concept,
+            WorkflowPattern: n_WorkflowPattern,
+            Block: n_Block,
+            Step: n_Step,
+            Tool: n_Tool,
+            SchemaVersion: n_SchemaVersion,
+            EmbeddingJob: n_EmbeddingJob
+        },
+        vectors: {
+            SessionEmbedding: v_SessionEmbedding,
+            ActivityEmbedding: v_ActivityEmbedding,
+            ConceptEmbedding: v_ConceptEmbedding
+        },
+        edges: {
+            UserOwnsNode: e_UserOwnsNode,
+            DependsOn: e_DependsOn,
+            UserOwnsSession: e_UserOwnsSession,
+            SessionInNode: e_SessionInNode,
+            NodeContainsSession: e_NodeContainsSession,
+            Follows: e_Follows,
+            ActivityInSession: e_ActivityInSession,
+            SwitchesTo: e_SwitchesTo,
+            ActivityMentionsEntity: e_ActivityMentionsEntity,
+            ActivityRelatedToConcept: e_ActivityRelatedToConcept,
+            BlockUsesTool: e_BlockUsesTool,
+            BlockRelatesConcept: e_BlockRelatesConcept,
+            RelatesTo: e_RelatesTo,
+            UserHasPattern: e_UserHasPattern,
+            UserHasBlock: e_UserHasBlock,
+            BlockContainsStep: e_BlockContainsStep,
+            StepEvidencedBy: e_StepEvidencedBy,
+            PatternContainsBlock: e_PatternContainsBlock,
+            PatternOccursInSession: e_PatternOccursInSession,
+            NextBlock: e_NextBlock,
+            ConceptHasEmbedding: e_ConceptHasEmbedding,
+            SessionHasEmbedding: e_SessionHasEmbedding,
+            ActivityHasEmbedding: e_ActivityHasEmbedding
+        }
+    }
 
 QUERY GetAllActivitiesForBackfill(start: I64, end_range: I64) =>
     activities <- N<Activity>::RANGE(start, end_range)
@@ -204,6 +1211,293 @@ QUERY GetAllSessionsForBackfill(start: I64, end_range: I64) =>
     sessions <- N<Session>::RANGE(start, end_range)
     RETURN sessions
 
+// Paginated nodes with no linked embedding, for the embedding backfill worker
+QUERY GetSessionsMissingEmbeddings(start: I64, end_range: I64) =>
+    sessions <- N<Session>::WHERE(!EXISTS(_::Out<SessionHasEmbedding>))::RANGE(start, end_range)
+    RETURN sessions
+
+QUERY GetActivitiesMissingEmbeddings(start: I64, end_range: I64) =>
+    activities <- N<Activity>::WHERE(!EXISTS(_::Out<ActivityHasEmbedding>))::RANGE(start, end_range)
+    RETURN activities
+
+// Paginated activities written before Activity.day existed, for BackfillActivityDays
+QUERY GetActivitiesMissingDay(start: I64, end_range: I64) =>
+    activities <- N<Activity>::WHERE(_::{day}::EQ(""))::RANGE(start, end_range)
+    RETURN activities::{screenshot_external_id, timestamp}
+
+// Paginated concepts with a description, for EmbedConceptsBatch re-embeds
+QUERY GetConceptsForReembed(start: I64, end_range: I64) =>
+    concepts <- N<Concept>::WHERE(_::{description}::NEQ(""))::RANGE(start, end_range)
+    RETURN concepts::{name}
+
+// ============================================================================
+// MCP TOOLS
+// ============================================================================
+
+// MCP clients can only call queries annotated with #[mcp]. scripts/check-mcp-readonly.sh (run by
+// `make check` and the Helix image build) rejects any annotated query that writes. The user key is
+// a caller-supplied argument, not bound to the MCP client, so the endpoint is off in helix.toml.
+
+// Static description of the graph for agent self-discovery: node, vector and edge labels with their
+// properties, and every #[mcp] query with its parameters. Generated by
+// scripts/generate-describe-schema.mjs from schema.hx and this file; do not edit by hand
+#[mcp]
+QUERY DescribeSchema() =>
+    RETURN {
+        nodes: [
+            {label: "User", properties: ["external_id: String", "metadata: String", "cohort_opt_in: Boolean", "created_at: Date", "updated_at: Date"]},
+            {label: "TimelineNode", properties: ["external_id: String", "node_type: String", "title: String", "metadata: String", "created_at: Date", "updated_at: Date"]},
+            {label: "Session", properties: ["external_id: String", "start_time: Date", "end_time: Date", "duration_seconds: I64", "screenshot_count: I64", "workflow_primary: String", "workflow_secondary: String", "workflow_confidence: F64", "metadata: String", "created_at: Date", "updated_at: Date"]},
+            {label: "Activity", properties: ["screenshot_external_id: String", "workflow_tag: String", "timestamp: Date", "day: String", "summary: String", "confidence: F64", "metadata: String", "created_at: Date", "updated_at: Date"]},
+            {label: "Entity", properties: ["name: String", "entity_type: String", "metadata: String", "created_at: Date", "updated_at: Date"]},
+            {label: "Concept", properties: ["name: String", "category: String", "relevance_score: F64", "description: String", "created_at: Date", "updated_at: Date"]},
+            {label: "WorkflowPattern", properties: ["user_id: String", "intent_category: String", "occurrence_count: I64", "metadata: String", "created_at: Date", "updated_at: Date"]},
+            {label: "Block", properties: ["user_id: String", "canonical_slug: String", "intent_label: String", "primary_tool: String", "occurrence_count: I64", "metadata: String", "created_at: Date", "updated_at: Date"]},
+            {label: "Step", properties: ["step_id: String", "session_id: String", "action_type: String", "description: String", "app_context: String", "timestamp: Date", "confidence: F64", "metadata: String", "created_at: Date", "updated_at: Date"]},
+            {label: "Tool", properties: ["canonical_name: String", "category: String", "metadata: String", "created_at: Date", "updated_at: Date"]},
+            {label: "SchemaVersion", properties: ["version: I64", "last_migration: String", "created_at: Date", "updated_at: Date"]},
+            {label: "EmbeddingJob", properties: ["job_id: String", "target_label: String", "status: String", "total: I64", "processed: I64", "error: String", "created_at: Date", "updated_at: Date"]}
+        ],
+        vectors: [
+            {label: "SessionEmbedding", properties: ["session_id: String"]},
+            {label: "ActivityEmbedding", properties: ["screenshot_external_id: String"]},
+            {label: "ConceptEmbedding", properties: ["concept_name: String", "description: String"]}
+        ],
+        edges: [
+            {label: "UserOwnsNode", from: "User", to: "TimelineNode", properties: []},
+            {label: "DependsOn", from: "TimelineNode", to: "TimelineNode", properties: ["dependency_type: String"]},
+            {label: "UserOwnsSession", from: "User", to: "Session", properties: []},
+            {label: "SessionInNode", from: "Session", to: "TimelineNode", properties: []},
+            {label: "NodeContainsSession", from: "TimelineNode", to: "Session", properties: []},
+            {label: "Follows", from: "Session", to: "Session", properties: ["gap_seconds: I64", "gap_bucket: String"]},
+            {label: "ActivityInSession", from: "Activity", to: "Session", properties: []},
+            {label: "SwitchesTo", from: "Activity", to: "Activity", properties: ["switch_type: String"]},
+            {label: "ActivityMentionsEntity", from: "Activity", to: "Entity", properties: ["context: String"]},
+            {label: "ActivityRelatedToConcept", from: "Activity", to: "Concept", properties: ["relevance: F64"]},
+            {label: "BlockUsesTool", from: "Block", to: "Tool", properties: ["is_primary: Boolean", "frequency: I64"]},
+            {label: "BlockRelatesConcept", from: "Block", to: "Concept", properties: ["relevance: F64"]},
+            {label: "UserHasPattern", from: "User", to: "WorkflowPattern", properties: []},
+            {label: "UserHasBlock", from: "User", to: "Block", properties: []},
+            {label: "BlockContainsStep", from: "Block", to: "Step", properties: ["order: I64"]},
+            {label: "StepEvidencedBy", from: "Step", to: "Activity", properties: ["screenshot_id: I64"]},
+            {label: "PatternContainsBlock", from: "WorkflowPattern", to: "Block", properties: ["order: I64"]},
+            {label: "PatternOccursInSession", from: "WorkflowPattern", to: "Session", properties: ["occurred_at: Date", "week: String"]},
+            {label: "NextBlock", from: "Block", to: "Block", properties: ["frequency: I64", "probability: F64"]},
+            {label: "ConceptHasEmbedding", from: "Concept", to: "ConceptEmbedding", properties: []},
+            {label: "SessionHasEmbedding", from: "Session", to: "SessionEmbedding", properties: []},
+            {label: "ActivityHasEmbedding", from: "Activity", to: "ActivityEmbedding", properties: []}
+        ],
+        queries: [
+            "GetUserByExternalId(external_id: String)",
+            "GetTimelineNodesByUser(user_key: String)",
+            "GetDependencyEdges(user_key: String)",
+            "GetSessionsByUser(user_key: String, start: I64, end_range: I64)",
+            "AggregateSessionsByWorkflow(user_key: String)",
+            "GetBlocksForConcept(concept_name: String, user_id: String)",
+            "GetConceptPath(user_key: String, from_name: String, to_name: String, limit: I64)",
+            "GetConceptNeighborhood(user_key: String, name: String, node_limit: I64)",
+            "GetConceptNeighborhoodShallow(user_key: String, name: String, node_limit: I64)",
+            "GetCrossSessionContext(user_key: String)",
+            "GetCrossSessionConcepts(user_key: String)",
+            "GetWorkflowPatterns(user_id: String)",
+            "GetBlocksByPattern(user_id: String, intent_category: String)",
+            "GetSessionsForPattern(user_id: String, intent_category: String)",
+            "GetBlocksByUser(user_id: String)",
+            "GetToolsForBlock(user_id: String, canonical_slug: String)",
+            "GetConceptsForBlock(user_id: String, canonical_slug: String)",
+            "GetBlockTransitionMatrix(user_id: String)",
+            "GetBlockPath(user_id: String, from_slug: String, to_slug: String)",
+            "GetBlockImportance(user_id: String, limit: I64)",
+            "GetStepsByUser(user_key: String)",
+            "GetSimilarSessionsToSession(user_key: String, session_external_id: String, limit: I64, top_k: I64)",
+            "GetSimilarActivitiesToActivity(user_key: String, screenshot_external_id: String, limit: I64, top_k: I64)",
+            "RecommendConceptsForSession(user_key: String, session_external_id: String, limit: I64, top_k: I64)",
+            "GetWorkflowTagDistribution(user_key: String)",
+            "GetTopConceptsForUser(user_key: String, limit: I64)",
+            "GetDailyActivityCounts(user_key: String, start_date: Date, end_date: Date)",
+            "GetSessionDurationStats(user_key: String)",
+            "GetSessionDurationStatsForWorkflow(user_key: String, workflow_primary: String)",
+            "GetEntityCooccurrence(user_key: String, limit: I64)",
+            "GetConceptCooccurrence(user_key: String, limit: I64)",
+            "GetWorkflowPatternTrends(user_id: String, start_date: Date, end_date: Date)",
+            "GetMostActiveTimelineNodes(user_key: String, limit: I64)",
+            "GetScreenshotTotals(user_key: String)",
+            "GetScreenshotTotalsInRange(user_key: String, start_date: Date, end_date: Date)",
+            "GetAverageConfidenceByWorkflowTag(user_key: String)",
+            "GetIdleGapStats(user_key: String)",
+            "GetWeeklyWorkflowSummary(user_key: String, week_start: Date, week_end: Date)",
+            "GetLongestSessions(user_key: String, start_date: Date, end_date: Date, limit: I64)",
+            "DescribeSchema()",
+            "SearchTimeline(user_key: String, query: String, limit: I64, top_k: I64)",
+            "SummarizeRecentActivity(user_key: String, days: I64, limit: I64)",
+            "GetWorkflowPatternInsights(user_id: String, recent_limit: I64)",
+            "LookupEntity(user_key: String, name: String, recent_limit: I64)",
+            "SuggestNextBlocks(user_id: String, canonical_slug: String, min_probability: F64, limit: I64)",
+            "GetConceptContext(user_key: String, name: String, limit: I64)",
+            "GetEntityContext(user_key: String, name: String, limit: I64)",
+            "GetUserGraphStats(user_key: String)"
+        ]
+    }
+
+// Natural-language search over a user's timeline sessions (filtered from a top_k candidate pool)
+#[mcp]
+QUERY SearchTimeline(user_key: String, query: String, limit: I64, top_k: I64) =>
+    sessions <- SearchV<SessionEmbedding>(Embed(query), MIN(MAX(top_k, limit), 1000))::WHERE(EXISTS(_::In<SessionHasEmbedding>::In<UserOwnsSession>::WHERE(_::{external_id}::EQ(user_key))))::RANGE(0, limit)::In<SessionHasEmbedding>
+    RETURN sessions::{external_id, start_time, end_time, duration_seconds, workflow_primary, workflow_secondary, metadata, node: _::Out<SessionInNode>::{title, node_type}}
+
+// Compact summary of a user's last `days` days for LLM context: session counts per workflow and
+// the top `limit` tools (via the blocks evidenced by the window's activities) and concepts
+#[mcp]
+QUERY SummarizeRecentActivity(user_key: String, days: I64, limit: I64) =>
+    since <- SUB(NOW, MUL(days, 86400))
+    sessions <- N<User>({external_id: user_key})::Out<UserOwnsSession>::WHERE(_::{start_time}::GTE(since))
+    activities <- sessions::In<ActivityInSession>
+    session_count <- sessions::COUNT
+    activity_count <- activities::COUNT
+    workflows <- sessions::GROUP_BY(workflow_primary)
+    tools <- activities::In<StepEvidencedBy>::In<BlockContainsStep>::Out<BlockUsesTool>::DEDUP::ORDER<Desc>(_::In<BlockUsesTool>::Out<BlockContainsStep>::Out<StepEvidencedBy>::WHERE(AND(_::{timestamp}::GTE(since), EXISTS(_::Out<ActivityInSession>::In<UserOwnsSession>::WHERE(_::{external_id}::EQ(user_key)))))::COUNT)::RANGE(0, limit)
+    concepts <- activities::Out<ActivityRelatedToConcept>::DEDUP::ORDER<Desc>(_::In<ActivityRelatedToConcept>::WHERE(AND(_::{timestamp}::GTE(since), EXISTS(_::Out<ActivityInSession>::In<UserOwnsSession>::WHERE(_::{external_id}::EQ(user_key)))))::COUNT)::RANGE(0, limit)
+    RETURN {
+        session_count: session_count,
+        activity_count: activity_count,
+        workflows: workflows,
+        top_tools: tools::{canonical_name, category},
+        top_concepts: concepts::{name, category}
+    }
+
+// A user's workflow patterns with their blocks and most recent occurrence sessions
+#[mcp]
+QUERY GetWorkflowPatternInsights(user_id: String, recent_limit: I64) =>
+    patterns <- N<User>({external_id: user_id})::Out<UserHasPattern>
+    RETURN patterns::{
+        intent_category,
+        occurrence_count,
+        metadata,
+        blocks: _::Out<PatternContainsBlock>::{canonical_slug, intent_label, primary_tool, occurrence_count},
+        recent_sessions: _::Out<PatternOccursInSession>::ORDER<Desc>(_::{start_time})::RANGE(0, recent_limit)::{external_id, start_time, duration_seconds, workflow_primary}
+    }
+
+// Entity details with the user's mention count and their most recent activities/sessions that mention it
+#[mcp]
+QUERY LookupEntity(user_key: String, name: String, recent_limit: I64) =>
+    entity <- N<Entity>({name: name})
+    activities <- entity::In<ActivityMentionsEntity>::WHERE(EXISTS(_::Out<ActivityInSession>::In<UserOwnsSession>::WHERE(_::{external_id}::EQ(user_key))))
+    frequency <- activities::COUNT
+    recent_activities <- activities::ORDER<Desc>(_::{timestamp})::RANGE(0, recent_limit)
+    RETURN {
+        entity: entity::{name, entity_type, metadata},
+        frequency: frequency,
+        recent_activities: recent_activities::{screenshot_external_id, timestamp, workflow_tag, summary, session: _::Out<ActivityInSession>::{external_id, start_time, workflow_primary}}
+    }
+
+// Up to `limit` blocks the user moves to after the given block with probability >= min_probability,
+// most likely first (pass 0.0 to rank every transition)
+#[mcp]
+QUERY SuggestNextBlocks(user_id: String, canonical_slug: String, min_probability: F64, limit: I64) =>
+    transitions <- N<User>({external_id: user_id})::Out<UserHasBlock>::WHERE(_::{canonical_slug}::EQ(canonical_slug))::OutE<NextBlock>::WHERE(_::{probability}::GTE(min_probability))::ORDER<Desc>(_::{probability})::RANGE(0, limit)
+    RETURN transitions::{probability, frequency, block: _::ToN::{canonical_slug, intent_label, primary_tool, occurrence_count}}
+
+// Small neighborhood around a concept (the user's linked activities, their distinct sessions and the
+// user's blocks related to it) for RAG grounding
+#[mcp]
+QUERY GetConceptContext(user_key: String, name: String, limit: I64) =>
+    concept <- N<Concept>({name: name})
+    activities <- concept::In<ActivityRelatedToConcept>::WHERE(EXISTS(_::Out<ActivityInSession>::In<UserOwnsSession>::WHERE(_::{external_id}::EQ(user_key))))::ORDER<Desc>(_::{timestamp})::RANGE(0, limit)
+    blocks <- concept::InE<BlockRelatesConcept>::WHERE(EXISTS(_::FromN::In<UserHasBlock>::WHERE(_::{external_id}::EQ(user_key))))::ORDER<Desc>(_::{relevance})::RANGE(0, limit)
+    RETURN {
+        concept: concept::{name, category, description},
+        activities: activities::{screenshot_external_id, timestamp, workflow_tag, summary},
+        sessions: activities::Out<ActivityInSession>::DEDUP::{external_id, start_time, workflow_primary, duration_seconds},
+        blocks: blocks::{relevance, block: _::FromN::{canonical_slug, intent_label, primary_tool, occurrence_count}}
+    }
+
+// Small neighborhood around an entity (the user's mentioning activities and their distinct sessions) for RAG grounding
+#[mcp]
+QUERY GetEntityContext(user_key: String, name: String, limit: I64) =>
+    entity <- N<Entity>({name: name})
+    activities <- entity::In<ActivityMentionsEntity>::WHERE(EXISTS(_::Out<ActivityInSession>::In<UserOwnsSession>::WHERE(_::{external_id}::EQ(user_key))))::ORDER<Desc>(_::{timestamp})::RANGE(0, limit)
+    RETURN {
+        entity: entity::{name, entity_type, metadata},
+        activities: activities::{screenshot_external_id, timestamp, workflow_tag, summary},
+        sessions: activities::Out<ActivityInSession>::DEDUP::{external_id, start_time, workflow_primary, duration_seconds}
+    }
+
+// How much history a user has, so agents can gauge it before running deeper queries
+#[mcp]
+QUERY GetUserGraphStats(user_key: String) =>
+    user <- N<User>({external_id: user_key})
+    sessions <- user::Out<UserOwnsSession>
+    activities <- sessions::In<ActivityInSession>
+    blocks <- user::Out<UserHasBlock>
+    session_count <- sessions::COUNT
+    activity_count <- activities::COUNT
+    block_count <- blocks::COUNT
+    pattern_count <- user::Out<UserHasPattern>::COUNT
+    concept_count <- activities::Out<ActivityRelatedToConcept>::DEDUP::COUNT
+    timeline_node_count <- user::Out<UserOwnsNode>::COUNT
+    entity_count <- activities::Out<ActivityMentionsEntity>::DEDUP::COUNT
+    step_count <- blocks::Out<BlockContainsStep>::COUNT
+    tool_count <- blocks::Out<BlockUsesTool>::DEDUP::COUNT
+    tools <- blocks::GROUP_BY(primary_tool)
+    RETURN {
+        timeline_nodes: timeline_node_count,
+        sessions: session_count,
+        activities: activity_count,
+        entities: entity_count,
+        blocks: block_count,
+        patterns: pattern_count,
+        concepts: concept_count,
+        steps: step_count,
+        tool_count: tool_count,
+        tools: tools
+    }
+
+// ============================================================================
+// DEBUG
+// ============================================================================
+
+// Owning user of a session, via UserOwnsSession
+QUERY GetUserForSession(session_external_id: String) =>
+    user <- N<Session>({external_id: session_external_id})::In<UserOwnsSession>
+    RETURN user
+
+// HQL traversals are typed by edge label, so "all edges between two nodes" is spelled out
+// per node pair. These cover the session-ingestion pairs only (user, node, session, activity,
+// entity, concept), for spotting duplicated link calls; pattern, block, step and tool edges are not covered.
+
+QUERY GetEdgesBetweenUserAndSession(user_key: String, session_external_id: String) =>
+    user <- N<User>({external_id: user_key})
+    owns <- user::OutE<UserOwnsSession>::WHERE(_::ToN::{external_id}::EQ(session_external_id))
+    RETURN {UserOwnsSession: owns}
+
+QUERY GetEdgesBetweenUserAndNode(user_key: String, node_external_id: String) =>
+    user <- N<User>({external_id: user_key})
+    owns <- user::OutE<UserOwnsNode>::WHERE(_::ToN::{external_id}::EQ(node_external_id))
+    RETURN {UserOwnsNode: owns}
+
+QUERY GetEdgesBetweenSessionAndNode(session_external_id: String, node_external_id: String) =>
+    session <- N<Session>({external_id: session_external_id})
+    node <- N<TimelineNode>({external_id: node_external_id})
+    in_node <- session::OutE<SessionInNode>::WHERE(_::ToN::{external_id}::EQ(node_external_id))
+    contains <- node::OutE<NodeContainsSession>::WHERE(_::ToN::{external_id}::EQ(session_external_id))
+    RETURN {SessionInNode: in_node, NodeContainsSession: contains}
+
+QUERY GetEdgesBetweenActivityAndSession(screenshot_external_id: String, session_external_id: String) =>
+    activity <- N<Activity>({screenshot_external_id: screenshot_external_id})
+    in_session <- activity::OutE<ActivityInSession>::WHERE(_::ToN::{external_id}::EQ(session_external_id))
+    RETURN {ActivityInSession: in_session}
+
+QUERY GetEdgesBetweenActivityAndEntity(screenshot_external_id: String, entity_name: String) =>
+    activity <- N<Activity>({screenshot_external_id: screenshot_external_id})
+    mentions <- activity::OutE<ActivityMentionsEntity>::WHERE(_::ToN::{name}::EQ(entity_name))
+    RETURN {ActivityMentionsEntity: mentions}
+
+QUERY GetEdgesBetweenActivityAndConcept(screenshot_external_id: String, concept_name: String) =>
+    activity <- N<Activity>({screenshot_external_id: screenshot_external_id})
+    related <- activity::OutE<ActivityRelatedToConcept>::WHERE(_::ToN::{name}::EQ(concept_name))
+    legacy <- activity::OutE<RelatesTo>::WHERE(_::ToN::{name}::EQ(concept_name))
+    RETURN {ActivityRelatedToConcept: related, RelatesTo: legacy}
+
 // ============================================================================
 // HEALTH CHECK
 // ============================================================================
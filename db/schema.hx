@@ -1,132 +1,334 @@
 // ============================================================================
 // Helix DB Schema for Lighthouse Journey Timeline
 // Graph RAG Workflow Analysis
+//
+// schema::2 is the canonical schema. Instances still running the legacy
+// variant (schema::1) are converged by the migrations in migrations.hx.
 // ============================================================================
 
-// ============================================================================
-// VERTEX NODES
-// ============================================================================
+schema::2 {
+    // ============================================================================
+    // VERTEX NODES
+    // ============================================================================
 
-// User node
-N::User {
-    external_id: String,
-    metadata: String
-}
+    // User node
+    N::User {
+        UNIQUE INDEX external_id: String,
+        metadata: String,
+        // Set when the user agrees to appear in other users' cohort analysis (GetSimilarUsers)
+        cohort_opt_in: Boolean DEFAULT false,
+        created_at: Date DEFAULT NOW,
+        updated_at: Date DEFAULT NOW
+    }
 
-// Timeline node
-N::TimelineNode {
-    external_id: String,
-    node_type: String,
-    title: String,
-    metadata: String
-}
+    // Timeline node
+    N::TimelineNode {
+        UNIQUE INDEX external_id: String,
+        node_type: String,
+        title: String,
+        metadata: String,
+        created_at: Date DEFAULT NOW,
+        updated_at: Date DEFAULT NOW
+    }
 
-// Session node
-N::Session {
-    external_id: String,
-    start_time: Date,
-    end_time: Date,
-    duration_seconds: I64,
-    screenshot_count: I64,
-    workflow_primary: String,
-    workflow_secondary: String,
-    workflow_confidence: F64,
-    metadata: String
-}
+    // Session node (user_key is legacy and no longer written; ownership is the UserOwnsSession edge)
+    N::Session {
+        UNIQUE INDEX external_id: String,
+        user_key: String DEFAULT "",
+        start_time: Date,
+        end_time: Date,
+        duration_seconds: I64,
+        screenshot_count: I64,
+        workflow_primary: String,
+        workflow_secondary: String,
+        workflow_confidence: F64,
+        metadata: String,
+        created_at: Date DEFAULT NOW,
+        updated_at: Date DEFAULT NOW
+    }
 
-// Activity node
-N::Activity {
-    screenshot_external_id: String,
-    workflow_tag: String,
-    timestamp: Date,
-    summary: String,
-    confidence: F64,
-    metadata: String
-}
+    // Activity node (day is the UTC YYYY-MM-DD of timestamp, supplied by writers for day bucketing)
+    N::Activity {
+        UNIQUE INDEX screenshot_external_id: String,
+        workflow_tag: String,
+        timestamp: Date,
+        day: String DEFAULT "",
+        summary: String,
+        confidence: F64,
+        metadata: String,
+        created_at: Date DEFAULT NOW,
+        updated_at: Date DEFAULT NOW
+    }
 
-// Entity node
-N::Entity {
-    INDEX name: String,
-    entity_type: String,
-    metadata: String
-}
+    // Entity node
+    N::Entity {
+        UNIQUE INDEX name: String,
+        entity_type: String,
+        metadata: String,
+        created_at: Date DEFAULT NOW,
+        updated_at: Date DEFAULT NOW
+    }
 
-// Concept node
-N::Concept {
-    INDEX name: String,
-    category: String,
-    relevance_score: F64
-}
+    // Concept node
+    N::Concept {
+        UNIQUE INDEX name: String,
+        category: String,
+        relevance_score: F64 DEFAULT 0.0,
+        description: String DEFAULT "",
+        created_at: Date DEFAULT NOW,
+        updated_at: Date DEFAULT NOW
+    }
 
-// WorkflowPattern node
-N::WorkflowPattern {
-    intent_category: String,
-    occurrence_count: I64,
-    metadata: String
-}
+    // WorkflowPattern node (one per user and intent_category; user_id mirrors the UserHasPattern owner)
+    N::WorkflowPattern {
+        user_id: String DEFAULT "",
+        INDEX intent_category: String,
+        occurrence_count: I64,
+        metadata: String,
+        created_at: Date DEFAULT NOW,
+        updated_at: Date DEFAULT NOW
+    }
 
-// Block node
-N::Block {
-    canonical_slug: String,
-    intent_label: String,
-    primary_tool: String,
-    occurrence_count: I64,
-    metadata: String
-}
+    // Block node (one per user and canonical_slug; user_id mirrors the UserHasBlock owner)
+    N::Block {
+        user_id: String DEFAULT "",
+        INDEX canonical_slug: String,
+        intent_label: String,
+        primary_tool: String,
+        occurrence_count: I64,
+        metadata: String,
+        created_at: Date DEFAULT NOW,
+        updated_at: Date DEFAULT NOW
+    }
 
-// Tool node
-N::Tool {
-    INDEX canonical_name: String,
-    category: String,
-    metadata: String
-}
+    // Step node (fine-grained UI action within a block)
+    N::Step {
+        UNIQUE INDEX step_id: String,
+        session_id: String,
+        action_type: String,
+        description: String,
+        app_context: String,
+        timestamp: Date,
+        confidence: F64,
+        metadata: String,
+        created_at: Date DEFAULT NOW,
+        updated_at: Date DEFAULT NOW
+    }
 
-// ============================================================================
-// EDGE RELATIONSHIPS
-// ============================================================================
+    // Tool node
+    N::Tool {
+        UNIQUE INDEX canonical_name: String,
+        category: String,
+        metadata: String,
+        created_at: Date DEFAULT NOW,
+        updated_at: Date DEFAULT NOW
+    }
 
-E::UserOwnsNode {
-    From: User,
-    To: TimelineNode
-}
+    // SchemaVersion node (singleton, written by migrations)
+    N::SchemaVersion {
+        version: I64,
+        last_migration: String,
+        created_at: Date DEFAULT NOW,
+        updated_at: Date DEFAULT NOW
+    }
 
-E::UserOwnsSession {
-    From: User,
-    To: Session
-}
+    // EmbeddingJob node (bulk re-embed progress, written by the client-side worker and polled by clients)
+    N::EmbeddingJob {
+        UNIQUE INDEX job_id: String,
+        target_label: String,
+        status: String DEFAULT "pending",
+        total: I64,
+        processed: I64 DEFAULT 0,
+        error: String DEFAULT "",
+        created_at: Date DEFAULT NOW,
+        updated_at: Date DEFAULT NOW
+    }
 
-E::SessionInNode {
-    From: Session,
-    To: TimelineNode
-}
+    // ============================================================================
+    // VECTOR EMBEDDINGS
+    // ============================================================================
 
-E::ActivityInSession {
-    From: Activity,
-    To: Session
-}
+    // Session embedding (session summary, configured embedding_model)
+    V::SessionEmbedding {
+        session_id: String
+    }
 
-E::ActivityMentionsEntity {
-    From: Activity,
-    To: Entity,
-    Properties: {
-        context: String
+    // Activity embedding (activity summary, configured embedding_model)
+    V::ActivityEmbedding {
+        screenshot_external_id: String
     }
-}
 
-E::ActivityRelatedToConcept {
-    From: Activity,
-    To: Concept,
-    Properties: {
-        relevance: F64
+    // Concept embedding (description, configured embedding_model)
+    V::ConceptEmbedding {
+        concept_name: String,
+        description: String
     }
-}
 
-E::UserHasPattern {
-    From: User,
-    To: WorkflowPattern
-}
+    // ============================================================================
+    // EDGE RELATIONSHIPS
+    // ============================================================================
+
+    E::UserOwnsNode {
+        From: User,
+        To: TimelineNode
+    }
+
+    E::DependsOn {
+        From: TimelineNode,
+        To: TimelineNode,
+        Properties: {
+            dependency_type: String
+        }
+    }
+
+    E::UserOwnsSession {
+        From: User,
+        To: Session
+    }
 
-E::UserHasBlock {
-    From: User,
-    To: Block
+    E::SessionInNode {
+        From: Session,
+        To: TimelineNode
+    }
+
+    // Inverse of SessionInNode; both directions are written together
+    E::NodeContainsSession {
+        From: TimelineNode,
+        To: Session
+    }
+
+    // Consecutive sessions of the same user. gap_bucket is the idle-gap histogram bucket
+    // ("<5m", "5-30m", "30m-2h", "2-8h", ">8h"), supplied by writers alongside gap_seconds
+    E::Follows {
+        From: Session,
+        To: Session,
+        Properties: {
+            gap_seconds: I64,
+            gap_bucket: String
+        }
+    }
+
+    E::ActivityInSession {
+        From: Activity,
+        To: Session
+    }
+
+    E::SwitchesTo {
+        From: Activity,
+        To: Activity,
+        Properties: {
+            switch_type: String
+        }
+    }
+
+    E::ActivityMentionsEntity {
+        From: Activity,
+        To: Entity,
+        Properties: {
+            context: String
+        }
+    }
+
+    E::ActivityRelatedToConcept {
+        From: Activity,
+        To: Concept,
+        Properties: {
+            relevance: F64
+        }
+    }
+
+    E::BlockUsesTool {
+        From: Block,
+        To: Tool,
+        Properties: {
+            is_primary: Boolean,
+            frequency: I64
+        }
+    }
+
+    E::BlockRelatesConcept {
+        From: Block,
+        To: Concept,
+        Properties: {
+            relevance: F64
+        }
+    }
+
+    // Legacy label for ActivityRelatedToConcept, drained by ConsolidateRelatesToEdges; never written
+    E::RelatesTo {
+        From: Activity,
+        To: Concept,
+        Properties: {
+            relevance: F64
+        }
+    }
+
+    E::UserHasPattern {
+        From: User,
+        To: WorkflowPattern
+    }
+
+    E::UserHasBlock {
+        From: User,
+        To: Block
+    }
+
+    E::BlockContainsStep {
+        From: Block,
+        To: Step,
+        Properties: {
+            order: I64
+        }
+    }
+
+    E::StepEvidencedBy {
+        From: Step,
+        To: Activity,
+        Properties: {
+            screenshot_id: I64
+        }
+    }
+
+    E::PatternContainsBlock {
+        From: WorkflowPattern,
+        To: Block,
+        Properties: {
+            order: I64
+        }
+    }
+
+    // week is the ISO week of occurred_at ("2026-W42"), supplied by writers for trend bucketing
+    E::PatternOccursInSession {
+        From: WorkflowPattern,
+        To: Session,
+        Properties: {
+            occurred_at: Date,
+            week: String DEFAULT ""
+        }
+    }
+
+    // Block transition mined by MineBlockSequences; frequency starts at 0 so the miner can accumulate it
+    E::NextBlock {
+        From: Block,
+        To: Block,
+        Properties: {
+            frequency: I64 DEFAULT 0,
+            probability: F64
+        }
+    }
+
+    E::ConceptHasEmbedding {
+        From: Concept,
+        To: ConceptEmbedding
+    }
+
+    E::SessionHasEmbedding {
+        From: Session,
+        To: SessionEmbedding
+    }
+
+    E::ActivityHasEmbedding {
+        From: Activity,
+        To: ActivityEmbedding
+    }
 }
@@ -16,6 +16,7 @@ import type { Logger } from '../core/logger.js';
 import type { Pool } from 'pg';
 import type { NodePgDatabase } from 'drizzle-orm/node-postgres';
 import type { EmbeddingService } from './interfaces/embedding.service.interface.js';
+import { EMBEDDING_DIMENSIONS } from './openai-embedding.service.js';
 
 // ============================================================================
 // HELIX CLIENT TYPE (from helix-ts SDK)
@@ -32,6 +33,28 @@ interface HelixClient {
 // eslint-disable-next-line @typescript-eslint/no-explicit-any
 let HelixDBClass: any;
 
+// User-scoped vector searches filter after the HNSW search, so they over-fetch
+// limit * SEARCH_CANDIDATE_MULTIPLIER candidates before keeping `limit` hits
+const SEARCH_CANDIDATE_MULTIPLIER = 5;
+
+// Distance functions accepted by RerankMMR
+export type MmrDistance = 'cosine' | 'euclidean' | 'dotproduct';
+
+const MMR_DISTANCES: readonly string[] = ['cosine', 'euclidean', 'dotproduct'];
+
+// Reciprocal rank fusion constant: a hit at rank r scores 1 / (RRF_K + r) in each list it appears in
+const RRF_K = 60;
+
+// Every vector sent to Helix must be EMBEDDING_DIMENSIONS long: the output size of the one
+// embedding model shared with helix.toml, so vectors of any other length are rejected.
+function assertEmbeddingDimensions(embedding: number[], label: string): void {
+  if (embedding.length !== EMBEDDING_DIMENSIONS) {
+    throw new Error(
+      `${label} embedding has ${embedding.length} dimensions, expected ${EMBEDDING_DIMENSIONS}`
+    );
+  }
+}
+
 // ============================================================================
 // TYPES AND INTERFACES
 // ============================================================================
@@ -102,6 +125,50 @@ export interface BlockData {
   metadata?: Record<string, unknown>;
 }
 
+export interface DependencyEdge {
+  from: string;
+  to: string;
+  dependencyType: string;
+}
+
+export interface SearchEverythingHit {
+  type: 'session' | 'activity' | 'concept';
+  id: string;
+  similarity: number;
+  rrfScore: number;
+  node: unknown;
+}
+
+export interface ConceptRecommendation {
+  conceptName: string;
+  description: string;
+  similarity: number;
+  rrfScore: number;
+  fromGraph: boolean;
+}
+
+export interface ScreenshotTotals {
+  total: number;
+  byWorkflow: Record<string, number>;
+}
+
+export interface BlockComponent {
+  component: number;
+  size: number;
+  blocks: string[];
+}
+
+export interface StepSequence {
+  actions: string[];
+  support: number;
+  occurrences: number;
+}
+
+export interface ConceptClusterAssignment {
+  concept: string;
+  cluster: number;
+}
+
 export interface StepData {
   sessionId: string;
   actionType: string;
@@ -110,6 +177,137 @@ export interface StepData {
   metadata?: Record<string, unknown>;
 }
 
+// ============================================================================
+// DEPENDENCY GRAPH HELPERS
+// ============================================================================
+
+function buildAdjacency(edges: DependencyEdge[]): Map<string, string[]> {
+  const adjacency = new Map<string, string[]>();
+  for (const edge of edges) {
+    if (!adjacency.has(edge.to)) adjacency.set(edge.to, []);
+    const targets = adjacency.get(edge.from) ?? [];
+    targets.push(edge.to);
+    adjacency.set(edge.from, targets);
+  }
+  return adjacency;
+}
+
+function reaches(adjacency: Map<string, string[]>, start: string, target: string): boolean {
+  const seen = new Set<string>([start]);
+  const queue = [start];
+  for (let i = 0; i < queue.length; i++) {
+    const node = queue[i];
+    if (node === target) return true;
+    for (const next of adjacency.get(node) ?? []) {
+      if (!seen.has(next)) {
+        seen.add(next);
+        queue.push(next);
+      }
+    }
+  }
+  return false;
+}
+
+/**
+ * Depth-first search for cycles; each cycle is returned as its node keys,
+ * starting and ending at the same node
+ */
+function findDependencyCycles(adjacency: Map<string, string[]>): string[][] {
+  const cycles: string[][] = [];
+  const state = new Map<string, 'visiting' | 'done'>();
+  const path: string[] = [];
+
+  const visit = (node: string): void => {
+    state.set(node, 'visiting');
+    path.push(node);
+    for (const next of adjacency.get(node) ?? []) {
+      const nextState = state.get(next);
+      if (nextState === 'visiting') {
+        cycles.push([...path.slice(path.indexOf(next)), next]);
+      } else if (nextState === undefined) {
+        visit(next);
+      }
+    }
+    path.pop();
+    state.set(node, 'done');
+  };
+
+  for (const node of adjacency.keys()) {
+    if (!state.has(node)) visit(node);
+  }
+  return cycles;
+}
+
+// ============================================================================
+// BLOCK AND CONCEPT GRAPH HELPERS
+// ============================================================================
+
+/**
+ * Undirected connected components via union-find, largest first. Nodes without
+ * edges come back as single-node components.
+ */
+function connectedComponents(nodes: string[], edges: Array<[string, string]>): string[][] {
+  const parent = new Map<string, string>(nodes.map((node) => [node, node]));
+  const find = (node: string): string => {
+    let root = node;
+    while (parent.get(root) !== root) root = parent.get(root) ?? root;
+    parent.set(node, root);
+    return root;
+  };
+
+  for (const [from, to] of edges) {
+    if (!parent.has(from)) parent.set(from, from);
+    if (!parent.has(to)) parent.set(to, to);
+    parent.set(find(from), find(to));
+  }
+
+  const components = new Map<string, string[]>();
+  for (const node of parent.keys()) {
+    const root = find(node);
+    const members = components.get(root) ?? [];
+    members.push(node);
+    components.set(root, members);
+  }
+  return Array.from(components.values()).sort((a, b) => b.length - a.length);
+}
+
+/**
+ * Weighted label propagation: every node starts in its own community and repeatedly adopts
+ * the label with the highest total edge weight among its neighbours, until no label changes.
+ * Nodes are visited in sorted order and ties go to the smallest label, so results are stable.
+ */
+function propagateLabels(adjacency: Map<string, Map<string, number>>, maxIterations: number): Map<string, string> {
+  const nodes = Array.from(adjacency.keys()).sort();
+  const labels = new Map<string, string>(nodes.map((node) => [node, node]));
+
+  for (let iteration = 0; iteration < maxIterations; iteration++) {
+    let changed = false;
+    for (const node of nodes) {
+      const weights = new Map<string, number>();
+      for (const [neighbour, weight] of adjacency.get(node) ?? []) {
+        const label = labels.get(neighbour) ?? neighbour;
+        weights.set(label, (weights.get(label) ?? 0) + weight);
+      }
+      if (weights.size === 0) continue;
+
+      let best = labels.get(node) ?? node;
+      let bestWeight = weights.get(best) ?? 0;
+      for (const [label, weight] of weights) {
+        if (weight > bestWeight || (weight === bestWeight && label < best)) {
+          best = label;
+          bestWeight = weight;
+        }
+      }
+      if (best !== labels.get(node)) {
+        labels.set(node, best);
+        changed = true;
+      }
+    }
+    if (!changed) break;
+  }
+  return labels;
+}
+
 // ============================================================================
 // HELIX GRAPH SERVICE
 // ============================================================================
@@ -296,6 +494,32 @@ export class HelixGraphService {
     }, 'upsertUser');
   }
 
+  /**
+   * Opt a user in to (or out of) other users' cohort analysis
+   */
+  async setCohortOptIn(userId: number, optIn: boolean): Promise<void> {
+    if (!this.enabled) {
+      return;
+    }
+
+    const client = await this.ensureInitialized();
+
+    return this.withRetry(async () => {
+      try {
+        await client.query('SetUserCohortOptIn', {
+          external_id: `user_${userId}`,
+          cohort_opt_in: optIn,
+        });
+      } catch (error) {
+        this.logger.error('Failed to set cohort opt-in',
+          error instanceof Error ? error : new Error(String(error)),
+          { userId, optIn }
+        );
+        throw error;
+      }
+    }, 'setCohortOptIn');
+  }
+
   /**
    * Get user by external ID
    */
@@ -385,6 +609,120 @@ export class HelixGraphService {
     }
   }
 
+  /**
+   * Get every DependsOn edge among a user's timeline nodes
+   */
+  private async getDependencyEdges(userKey: string): Promise<DependencyEdge[]> {
+    const client = await this.ensureInitialized();
+    const result = await client.query<Array<{ from: string; to: string; dependency_type: string }>>(
+      'GetDependencyEdges',
+      { user_key: userKey }
+    );
+    return (result || []).map((edge) => ({
+      from: edge.from,
+      to: edge.to,
+      dependencyType: edge.dependency_type,
+    }));
+  }
+
+  /**
+   * Find DependsOn cycles among a user's timeline nodes (any length).
+   * Returns an empty list when the dependencies are valid.
+   */
+  async validateDependencies(userId: number): Promise<string[][]> {
+    if (!this.enabled) {
+      return [];
+    }
+
+    const edges = await this.getDependencyEdges(`user_${userId}`);
+    return findDependencyCycles(buildAdjacency(edges));
+  }
+
+  /**
+   * Link two timeline nodes so `fromNodeId` depends on `toNodeId`.
+   * Throws instead of writing when `toNodeId` already depends on `fromNodeId`,
+   * directly or transitively, since the link would close a cycle.
+   */
+  async linkNodeDependency(
+    fromNodeId: number | string,
+    toNodeId: number | string,
+    userId: number,
+    dependencyType: string
+  ): Promise<void> {
+    if (!this.enabled) {
+      return;
+    }
+
+    const client = await this.ensureInitialized();
+    const fromKey = `node_${fromNodeId}`;
+    const toKey = `node_${toNodeId}`;
+
+    const adjacency = buildAdjacency(await this.getDependencyEdges(`user_${userId}`));
+    if (fromKey === toKey || reaches(adjacency, toKey, fromKey)) {
+      throw new Error(`Dependency ${fromKey} -> ${toKey} would create a cycle`);
+    }
+
+    return this.withRetry(async () => {
+      try {
+        await client.query('LinkNodeDependency', {
+          from_external_id: fromKey,
+          to_external_id: toKey,
+          dependency_type: dependencyType,
+        });
+
+        this.logger.debug('Linked timeline node dependency', { fromKey, toKey, dependencyType });
+      } catch (error) {
+        this.logger.error('Failed to link timeline node dependency',
+          error instanceof Error ? error : new Error(String(error)),
+          { fromKey, toKey }
+        );
+        throw error;
+      }
+    }, 'linkNodeDependency');
+  }
+
+  /**
+   * Topological order of a user's timeline nodes for the planning view, as levels:
+   * level 0 has no dependencies and every node's dependencies sit in earlier levels.
+   * Throws when the DependsOn edges contain a cycle.
+   */
+  async getNodeExecutionOrder(userId: number): Promise<string[][]> {
+    if (!this.enabled) {
+      return [];
+    }
+
+    const nodes = (await this.getTimelineNodesByUser(userId)) as Array<{ external_id: string }>;
+    const adjacency = buildAdjacency(await this.getDependencyEdges(`user_${userId}`));
+    for (const node of nodes) {
+      if (!adjacency.has(node.external_id)) adjacency.set(node.external_id, []);
+    }
+
+    const cycles = findDependencyCycles(adjacency);
+    if (cycles.length > 0) {
+      throw new Error(
+        `Timeline node dependencies contain cycles: ${cycles.map((cycle) => cycle.join(' -> ')).join('; ')}`
+      );
+    }
+
+    const levels = new Map<string, number>();
+    const levelOf = (node: string): number => {
+      const known = levels.get(node);
+      if (known !== undefined) return known;
+      const dependencies = adjacency.get(node) ?? [];
+      const level = dependencies.length === 0 ? 0 : 1 + Math.max(...dependencies.map(levelOf));
+      levels.set(node, level);
+      return level;
+    };
+
+    const order: string[][] = [];
+    for (const node of adjacency.keys()) {
+      const level = levelOf(node);
+      if (!order[level]) order[level] = [];
+      order[level].push(node);
+    }
+    return order;
+  }
+
   // ============================================================================
   // SESSION OPERATIONS
   // ============================================================================
@@ -526,6 +864,7 @@ export class HelixGraphService {
           screenshot_external_id: String(activity.screenshotExternalId),
           workflow_tag: activity.workflowTag,
           timestamp: activity.timestamp.toISOString(),
+          day: activity.timestamp.toISOString().slice(0, 10),
           summary: activity.summary,
           confidence: activity.confidence,
           metadata: JSON.stringify(activity.metadata || {}),
@@ -610,8 +949,8 @@ export class HelixGraphService {
 
     try {
       // First get total activity count (with logging)
-      const countResult = await this.queryWithLogging<{ count?: number }>(client, 'GetActivityCount', {});
-      const totalCount = (countResult as any)?.count || 0;
+      const statsResult = await this.queryWithLogging<{ nodes?: { Activity?: number } }>(client, 'GetGraphStats', {});
+      const totalCount = statsResult?.nodes?.Activity || 0;
       this.logger.info('Starting ActivityInSession edge backfill', { totalCount });
 
       // Get all sessions to build a lookup map (with logging)
@@ -853,6 +1192,45 @@ export class HelixGraphService {
     }, 'upsertConcept');
   }
 
+  /**
+   * Upsert concepts with precomputed description embeddings in one transaction
+   */
+  async upsertConceptsWithEmbeddings(
+    concepts: Array<{ name: string; category: string; relevanceScore: number; description: string; embedding: number[] }>
+  ): Promise<void> {
+    if (!this.enabled || concepts.length === 0) {
+      return;
+    }
+
+    for (const concept of concepts) {
+      assertEmbeddingDimensions(concept.embedding, `Concept "${concept.name}"`);
+    }
+
+    const client = await this.ensureInitialized();
+
+    return this.withRetry(async () => {
+      try {
+        await client.query('BulkUpsertConceptsWithEmbeddings', {
+          concepts: concepts.map(concept => ({
+            name: concept.name,
+            category: concept.category,
+            relevance_score: concept.relevanceScore,
+            description: concept.description,
+            embedding: concept.embedding,
+          })),
+        });
+
+        this.logger.debug('Upserted concepts with embeddings in Helix DB', { count: concepts.length });
+      } catch (error) {
+        this.logger.error('Failed to upsert concepts with embeddings in Helix DB',
+          error instanceof Error ? error : new Error(String(error)),
+          { count: concepts.length }
+        );
+        throw error;
+      }
+    }, 'upsertConceptsWithEmbeddings');
+  }
+
   /**
    * Link activity to concept
    */
@@ -904,6 +1282,65 @@ export class HelixGraphService {
     );
   }
 
+  /**
+   * Cluster a user's concepts by label propagation over their co-occurrence graph, weighting each
+   * pair by the number of sessions they share. Cluster ids are dense, largest cluster first.
+   */
+  async getConceptClusters(
+    userId: number,
+    options: { conceptLimit?: number; maxIterations?: number } = {}
+  ): Promise<ConceptClusterAssignment[]> {
+    if (!this.enabled) {
+      return [];
+    }
+
+    const client = await this.ensureInitialized();
+
+    try {
+      const result = await client.query<Array<{
+        name: string;
+        cooccurring?: Array<{ name: string; shared_sessions: number }>;
+      }>>('GetConceptCooccurrence', {
+        user_key: `user_${userId}`,
+        limit: options.conceptLimit ?? 500,
+      });
+
+      const adjacency = new Map<string, Map<string, number>>();
+      const link = (from: string, to: string, weight: number): void => {
+        const neighbours = adjacency.get(from) ?? new Map<string, number>();
+        neighbours.set(to, Math.max(neighbours.get(to) ?? 0, weight));
+        adjacency.set(from, neighbours);
+      };
+      for (const concept of result || []) {
+        if (!adjacency.has(concept.name)) adjacency.set(concept.name, new Map());
+        for (const other of concept.cooccurring || []) {
+          link(concept.name, other.name, other.shared_sessions);
+          link(other.name, concept.name, other.shared_sessions);
+        }
+      }
+
+      const labels = propagateLabels(adjacency, options.maxIterations ?? 20);
+
+      const sizes = new Map<string, number>();
+      for (const label of labels.values()) sizes.set(label, (sizes.get(label) ?? 0) + 1);
+      const clusterIds = new Map<string, number>(
+        Array.from(sizes.entries())
+          .sort((a, b) => b[1] - a[1] || (a[0] < b[0] ? -1 : 1))
+          .map(([label], index) => [label, index])
+      );
+
+      return Array.from(labels.entries())
+        .map(([concept, label]) => ({ concept, cluster: clusterIds.get(label) ?? 0 }))
+        .sort((a, b) => a.cluster - b.cluster || (a.concept < b.concept ? -1 : 1));
+    } catch (error) {
+      this.logger.error('Failed to cluster concepts',
+        error instanceof Error ? error : new Error(String(error)),
+        { userId }
+      );
+      return [];
+    }
+  }
+
   // ============================================================================
   // CROSS-SESSION CONTEXT
   // ============================================================================
@@ -1769,6 +2206,112 @@ export class HelixGraphService {
     }
   }
 
+  /**
+   * Partition a user's blocks into connected components over NextBlock edges (direction
+   * ignored). Each component is a distinct routine; blocks with no transitions form their own.
+   * HQL traversals have a fixed depth, so components are computed here over the full
+   * transition matrix.
+   */
+  async getBlockComponents(userId: string): Promise<BlockComponent[]> {
+    if (!this.enabled) {
+      return [];
+    }
+
+    const client = await this.ensureInitialized();
+
+    try {
+      const [blocks, transitions] = await Promise.all([
+        client.query<Array<{ canonical_slug: string }>>('GetBlocksByUser', { user_id: userId }),
+        client.query<Array<{ from_slug: string; to_slug: string }>>('GetBlockTransitionMatrix', { user_id: userId }),
+      ]);
+
+      const components = connectedComponents(
+        (blocks || []).map((block) => block.canonical_slug),
+        (transitions || []).map((transition): [string, string] => [transition.from_slug, transition.to_slug])
+      );
+
+      return components.map((members, index) => ({
+        component: index,
+        size: members.length,
+        blocks: members.sort(),
+      }));
+    } catch (error) {
+      this.logger.error('Failed to get block components',
+        error instanceof Error ? error : new Error(String(error)),
+        { userId }
+      );
+      return [];
+    }
+  }
+
+  // ============================================================================
+  // STEP OPERATIONS
+  // ============================================================================
+
+  /**
+   * Mine frequent ordered step subsequences for a user, as candidate block definitions.
+   * Each session's steps in timestamp order form one chain; every contiguous run of
+   * minLength..maxLength action types is counted. support is the number of sessions
+   * containing the run, occurrences the total count. Runs below minSupport are dropped.
+   */
+  async mineStepSequences(
+    userId: number,
+    options: { minLength?: number; maxLength?: number; minSupport?: number; limit?: number } = {}
+  ): Promise<StepSequence[]> {
+    if (!this.enabled) {
+      return [];
+    }
+
+    const minLength = Math.max(2, options.minLength ?? 2);
+    const maxLength = Math.min(5, Math.max(minLength, options.maxLength ?? 3));
+    const minSupport = Math.max(1, options.minSupport ?? 2);
+    const limit = options.limit ?? 50;
+
+    const client = await this.ensureInitialized();
+
+    try {
+      const steps = await client.query<Array<{ session_id: string; action_type: string }>>('GetStepsByUser', {
+        user_key: `user_${userId}`,
+      });
+
+      const chains = new Map<string, string[]>();
+      for (const step of steps || []) {
+        const chain = chains.get(step.session_id) ?? [];
+        chain.push(step.action_type);
+        chains.set(step.session_id, chain);
+      }
+
+      const counts = new Map<string, StepSequence>();
+      for (const chain of chains.values()) {
+        const seenInSession = new Set<string>();
+        for (let length = minLength; length <= maxLength; length++) {
+          for (let start = 0; start + length <= chain.length; start++) {
+            const actions = chain.slice(start, start + length);
+            const key = JSON.stringify(actions);
+            const sequence = counts.get(key) ?? { actions, support: 0, occurrences: 0 };
+            sequence.occurrences += 1;
+            if (!seenInSession.has(key)) {
+              seenInSession.add(key);
+              sequence.support += 1;
+            }
+            counts.set(key, sequence);
+          }
+        }
+      }
+
+      return Array.from(counts.values())
+        .filter((sequence) => sequence.support >= minSupport)
+        .sort((a, b) => b.support - a.support || b.actions.length - a.actions.length)
+        .slice(0, limit);
+    } catch (error) {
+      this.logger.error('Failed to mine step sequences',
+        error instanceof Error ? error : new Error(String(error)),
+        { userId }
+      );
+      return [];
+    }
+  }
+
   // ============================================================================
   // TOOL OPERATIONS
   // ============================================================================
@@ -1807,9 +2350,9 @@ export class HelixGraphService {
   // ============================================================================
 
   /**
-   * Search similar activities by embedding
+   * Search a user's activities by embedding
    */
-  async searchSimilarActivities(queryEmbedding: number[], limit: number = 10): Promise<unknown[]> {
+  async searchSimilarActivities(userKey: string, queryEmbedding: number[], limit: number = 10, minScore: number = 0): Promise<unknown[]> {
     if (!this.enabled) {
       return [];
     }
@@ -1817,9 +2360,13 @@ export class HelixGraphService {
     const client = await this.ensureInitialized();
 
     try {
+      assertEmbeddingDimensions(queryEmbedding, 'Activity query');
       const result = await client.query<unknown[]>('SearchSimilarActivities', {
+        user_key: userKey,
         query_embedding: queryEmbedding,
         limit: limit,
+        top_k: limit * SEARCH_CANDIDATE_MULTIPLIER,
+        min_score: minScore,
       });
       return result || [];
     } catch (error) {
@@ -1830,10 +2377,169 @@ export class HelixGraphService {
     }
   }
 
+  /**
+   * Search a user's sessions by embedding, MMR-reranked for diversity
+   */
+  async searchSimilarSessionsDiverse(
+    userKey: string,
+    queryEmbedding: number[],
+    limit: number = 10,
+    lambda: number = 0.7,
+    distance: MmrDistance = 'cosine'
+  ): Promise<unknown[]> {
+    if (!this.enabled) {
+      return [];
+    }
+
+    if (!MMR_DISTANCES.includes(distance)) {
+      throw new Error(`Unknown MMR distance "${distance}", expected one of ${MMR_DISTANCES.join(', ')}`);
+    }
+
+    const client = await this.ensureInitialized();
+
+    try {
+      assertEmbeddingDimensions(queryEmbedding, 'Session query');
+      const result = await client.query<unknown[]>('SearchSimilarSessionsDiverse', {
+        user_key: userKey,
+        query_embedding: queryEmbedding,
+        limit: limit,
+        top_k: limit * SEARCH_CANDIDATE_MULTIPLIER,
+        lambda: lambda,
+        distance: distance,
+      });
+      return result || [];
+    } catch (error) {
+      this.logger.error('Failed to search diverse sessions',
+        error instanceof Error ? error : new Error(String(error))
+      );
+      return [];
+    }
+  }
+
+  /**
+   * Search a user's sessions and activities plus concepts for one query, fused into a
+   * single ranking with reciprocal rank fusion. Ranks are used instead of raw scores
+   * because each label has its own HNSW index, so similarities are not comparable.
+   */
+  async searchEverything(userKey: string, query: string, limit: number = 10): Promise<SearchEverythingHit[]> {
+    if (!this.enabled) {
+      return [];
+    }
+
+    const client = await this.ensureInitialized();
+
+    try {
+      const result = await client.query<{
+        Session?: Array<{ session_id: string; score: number; session: unknown }>;
+        Activity?: Array<{ screenshot_external_id: string; score: number; activity: unknown }>;
+        Concept?: Array<{ concept_name: string; score: number; concept: unknown }>;
+      }>('SearchEverything', {
+        user_key: userKey,
+        query: query,
+        limit: limit,
+        top_k: limit * SEARCH_CANDIDATE_MULTIPLIER,
+      });
+
+      const ranked: SearchEverythingHit[][] = [
+        (result?.Session || []).map((hit) => ({
+          type: 'session' as const, id: hit.session_id, similarity: hit.score, rrfScore: 0, node: hit.session,
+        })),
+        (result?.Activity || []).map((hit) => ({
+          type: 'activity' as const, id: String(hit.screenshot_external_id), similarity: hit.score, rrfScore: 0, node: hit.activity,
+        })),
+        (result?.Concept || []).map((hit) => ({
+          type: 'concept' as const, id: hit.concept_name, similarity: hit.score, rrfScore: 0, node: hit.concept,
+        })),
+      ];
+
+      const fused: SearchEverythingHit[] = [];
+      for (const hits of ranked) {
+        hits
+          .sort((a, b) => b.similarity - a.similarity)
+          .forEach((hit, rank) => {
+            fused.push({ ...hit, rrfScore: 1 / (RRF_K + rank + 1) });
+          });
+      }
+
+      return fused.sort((a, b) => b.rrfScore - a.rrfScore).slice(0, limit);
+    } catch (error) {
+      this.logger.error('Failed to search everything',
+        error instanceof Error ? error : new Error(String(error)),
+        { userKey, query }
+      );
+      return [];
+    }
+  }
+
+  /**
+   * Recommend concepts for one of a user's sessions. Fuses the graph candidates (concepts of
+   * sessions on the same timeline node) and the nearest concepts overall with reciprocal rank
+   * fusion, so a concept found by both signals ranks above one found by either alone.
+   */
+  async recommendConceptsForSession(
+    userKey: string,
+    sessionExternalId: string,
+    limit: number = 10
+  ): Promise<ConceptRecommendation[]> {
+    if (!this.enabled) {
+      return [];
+    }
+
+    const client = await this.ensureInitialized();
+
+    try {
+      type ConceptHit = { concept_name: string; description: string; score: number };
+      const result = await client.query<{ graph?: ConceptHit[]; semantic?: ConceptHit[] }>(
+        'RecommendConceptsForSession',
+        {
+          user_key: userKey,
+          session_external_id: sessionExternalId,
+          limit: limit,
+          top_k: limit * SEARCH_CANDIDATE_MULTIPLIER,
+        }
+      );
+
+      const fused = new Map<string, ConceptRecommendation>();
+      const addRanked = (hits: ConceptHit[], fromGraph: boolean): void => {
+        [...hits]
+          .sort((a, b) => b.score - a.score)
+          .forEach((hit, rank) => {
+            const existing = fused.get(hit.concept_name);
+            const rrfScore = 1 / (RRF_K + rank + 1);
+            if (existing) {
+              existing.rrfScore += rrfScore;
+              existing.similarity = Math.max(existing.similarity, hit.score);
+              existing.fromGraph = existing.fromGraph || fromGraph;
+            } else {
+              fused.set(hit.concept_name, {
+                conceptName: hit.concept_name,
+                description: hit.description,
+                similarity: hit.score,
+                rrfScore,
+                fromGraph,
+              });
+            }
+          });
+      };
+      addRanked(result?.graph || [], true);
+      addRanked(result?.semantic || [], false);
+
+      return Array.from(fused.values())
+        .sort((a, b) => b.rrfScore - a.rrfScore)
+        .slice(0, limit);
+    } catch (error) {
+      this.logger.error('Failed to recommend concepts for session',
+        error instanceof Error ? error : new Error(String(error)),
+        { userKey, sessionExternalId }
+      );
+      return [];
+    }
+  }
+
   /**
    * Search similar concepts by embedding
    */
-  async searchSimilarConcepts(queryEmbedding: number[], limit: number = 10): Promise<unknown[]> {
+  async searchSimilarConcepts(queryEmbedding: number[], limit: number = 10, minScore: number = 0): Promise<unknown[]> {
     if (!this.enabled) {
       return [];
     }
@@ -1841,9 +2547,11 @@ export class HelixGraphService {
     const client = await this.ensureInitialized();
 
     try {
+      assertEmbeddingDimensions(queryEmbedding, 'Concept query');
       const result = await client.query<unknown[]>('SearchSimilarConcepts', {
         query_embedding: queryEmbedding,
         limit: limit,
+        min_score: minScore,
       });
       return result || [];
     } catch (error) {
@@ -1854,6 +2562,85 @@ export class HelixGraphService {
     }
   }
 
+  // ============================================================================
+  // STATISTICS OPERATIONS
+  // ============================================================================
+
+  /**
+   * Total screenshots across a user's sessions, optionally only sessions starting
+   * within a date range, with the per-workflow_primary sums
+   */
+  async getScreenshotTotals(userKey: string, range?: { start: Date; end: Date }): Promise<ScreenshotTotals> {
+    if (!this.enabled) {
+      return { total: 0, byWorkflow: {} };
+    }
+
+    const client = await this.ensureInitialized();
+
+    try {
+      const result = await client.query<{
+        total?: number;
+        sessions?: Array<{ workflow_primary: string; screenshot_count: number }>;
+      }>(
+        range ? 'GetScreenshotTotalsInRange' : 'GetScreenshotTotals',
+        range
+          ? { user_key: userKey, start_date: range.start.toISOString(), end_date: range.end.toISOString() }
+          : { user_key: userKey }
+      );
+
+      const byWorkflow: Record<string, number> = {};
+      for (const session of result?.sessions || []) {
+        byWorkflow[session.workflow_primary] = (byWorkflow[session.workflow_primary] || 0) + (session.screenshot_count || 0);
+      }
+
+      return { total: result?.total || 0, byWorkflow };
+    } catch (error) {
+      this.logger.error('Failed to get screenshot totals',
+        error instanceof Error ? error : new Error(String(error)),
+        { userKey }
+      );
+      return { total: 0, byWorkflow: {} };
+    }
+  }
+
+  /**
+   * Mean activity confidence for a user, overall and per workflow_tag
+   */
+  async getAverageConfidenceByWorkflowTag(
+    userKey: string
+  ): Promise<{ overall: number; byTag: Record<string, { mean: number; count: number }> }> {
+    if (!this.enabled) {
+      return { overall: 0, byTag: {} };
+    }
+
+    const client = await this.ensureInitialized();
+
+    try {
+      const result = await client.query<{
+        overall_confidence?: number;
+        activities?: Array<{ workflow_tag: string; confidence: number }>;
+      }>('GetAverageConfidenceByWorkflowTag', {
+        user_key: userKey,
+      });
+
+      const byTag: Record<string, { mean: number; count: number }> = {};
+      for (const activity of result?.activities || []) {
+        const group = byTag[activity.workflow_tag] || { mean: 0, count: 0 };
+        group.count += 1;
+        group.mean += ((activity.confidence || 0) - group.mean) / group.count;
+        byTag[activity.workflow_tag] = group;
+      }
+
+      return { overall: result?.overall_confidence || 0, byTag };
+    } catch (error) {
+      this.logger.error('Failed to get average confidence by workflow tag',
+        error instanceof Error ? error : new Error(String(error)),
+        { userKey }
+      );
+      return { overall: 0, byTag: {} };
+    }
+  }
+
   // ============================================================================
   // MIGRATION METHODS (Stubs - not needed for Helix as it has no legacy data)
   // ============================================================================
@@ -10,10 +10,15 @@ import type { Logger } from '../core/logger.js';
 import type { EmbeddingService } from './interfaces';
 import { getLangfuse } from '../core/langfuse.js';
 
+// Must match helix.toml's embedding_model: Helix embeds query text with that model and
+// compares it against vectors produced here (checked by scripts/check-embedding-model.sh)
+export const EMBEDDING_MODEL = 'text-embedding-3-small';
+export const EMBEDDING_DIMENSIONS = 1536;
+
 export class OpenAIEmbeddingService implements EmbeddingService {
   private openai: OpenAI;
-  private model = 'text-embedding-3-small';
-  private dimensions = 1536;
+  private model = EMBEDDING_MODEL;
+  private dimensions = EMBEDDING_DIMENSIONS;
   private logger?: Logger;
 
   // In-memory cache for query embeddings (same query text → same embedding)
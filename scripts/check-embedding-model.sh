@@ -0,0 +1,28 @@
+#!/bin/bash
+
+# Fail when helix.toml's embedding_model differs from the model the server embeds with.
+# Helix embeds query text (Embed) with its model and compares it against vectors the
+# server produced, so the two must always name the same model.
+# Usage: scripts/check-embedding-model.sh
+
+set -euo pipefail
+
+TOML=helix.toml
+SERVICE=packages/server/src/services/openai-embedding.service.ts
+
+helix_models=$(sed -n 's/^embedding_model = "\(.*\)"$/\1/p' "$TOML" | sort -u)
+server_model=$(sed -n "s/^export const EMBEDDING_MODEL = '\(.*\)';\$/\1/p" "$SERVICE")
+
+if [ -z "$server_model" ]; then
+  echo "$SERVICE: EMBEDDING_MODEL not found"
+  exit 1
+fi
+
+for model in $helix_models; do
+  if [ "$model" != "$server_model" ]; then
+    echo "$TOML: embedding_model \"$model\" does not match EMBEDDING_MODEL \"$server_model\" in $SERVICE"
+    exit 1
+  fi
+done
+
+echo "Embedding model is $server_model everywhere"
@@ -0,0 +1,24 @@
+#!/bin/bash
+
+# Fail when an #[mcp] query writes to the graph. MCP tool calls must stay read-only,
+# so every query exposed to agents is checked for write steps before Helix builds it.
+# Usage: scripts/check-mcp-readonly.sh [file.hx ...]   (defaults to db/queries.hx)
+
+set -euo pipefail
+
+if [ "$#" -eq 0 ]; then
+  set -- db/queries.hx
+fi
+
+awk '
+  /^#\[mcp\]/ { pending = 1; next }
+  /^QUERY / { name = $2; sub(/\(.*/, "", name); in_mcp = pending; pending = 0; next }
+  /^[^ \t]/ { in_mcp = 0; pending = 0 }
+  in_mcp && /(AddN|AddE|AddV|UpsertN|UpsertE|UpsertV|UPDATE|DROP)/ {
+    printf "%s:%d: #[mcp] query %s writes to the graph\n", FILENAME, FNR, name
+    failed = 1
+  }
+  END { exit failed ? 1 : 0 }
+' "$@"
+
+echo "All #[mcp] queries are read-only"
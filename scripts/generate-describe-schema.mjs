@@ -0,0 +1,108 @@
+#!/usr/bin/env node
+
+// Regenerate the DescribeSchema query in db/queries.hx from db/schema.hx and the #[mcp] queries,
+// so the description agents read cannot drift from the real schema.
+// Usage: node scripts/generate-describe-schema.mjs [--check]
+//   --check  exit 1 instead of writing when db/queries.hx is out of date
+
+import { readFileSync, writeFileSync } from 'node:fs';
+
+const SCHEMA_PATH = 'db/schema.hx';
+const QUERIES_PATH = 'db/queries.hx';
+
+// Kept in the schema only so migrations can read old data; never written, so not advertised
+const LEGACY = new Set(['Session.user_key', 'RelatesTo']);
+
+const HEADER = `// Static description of the graph for agent self-discovery: node, vector and edge labels with their
+// properties, and every #[mcp] query with its parameters. Generated by
+// scripts/generate-describe-schema.mjs from schema.hx and this file; do not edit by hand
+`;
+
+const INDENT = '            ';
+
+function parseProperties(body) {
+  return body
+    .split('\n')
+    .map((line) => line.trim().replace(/,$/, ''))
+    .filter((line) => line && !line.startsWith('//'))
+    .map((line) => line.replace(/^(UNIQUE )?INDEX /, '').replace(/ DEFAULT .*$/, ''));
+}
+
+function quoteAll(values) {
+  return values.map((value) => `"${value}"`).join(', ');
+}
+
+function describeSchema(schema) {
+  const nodes = [];
+  const vectors = [];
+  const edges = [];
+
+  for (const [, kind, label, body] of schema.matchAll(/^ {4}([NVE])::(\w+) \{([\s\S]*?)^ {4}\}/gm)) {
+    if (LEGACY.has(label)) continue;
+
+    if (kind === 'E') {
+      const from = body.match(/From: (\w+)/)[1];
+      const to = body.match(/To: (\w+)/)[1];
+      const propertiesBody = body.match(/Properties: \{([\s\S]*?)\}/);
+      const properties = propertiesBody ? parseProperties(propertiesBody[1]) : [];
+      edges.push(`{label: "${label}", from: "${from}", to: "${to}", properties: [${quoteAll(properties)}]}`);
+      continue;
+    }
+
+    const properties = parseProperties(body).filter(
+      (property) => !LEGACY.has(`${label}.${property.split(':')[0]}`)
+    );
+    (kind === 'N' ? nodes : vectors).push(`{label: "${label}", properties: [${quoteAll(properties)}]}`);
+  }
+
+  return { nodes, vectors, edges };
+}
+
+function describeQueries(queries) {
+  return [...queries.matchAll(/^#\[mcp\]\nQUERY (\w+\(.*\)) =>/gm)].map(([, signature]) => `"${signature}"`);
+}
+
+function render(schema, queries) {
+  const { nodes, vectors, edges } = describeSchema(schema);
+  const list = (items) => items.map((item) => INDENT + item).join(',\n');
+
+  return `${HEADER}#[mcp]
+QUERY DescribeSchema() =>
+    RETURN {
+        nodes: [
+${list(nodes)}
+        ],
+        vectors: [
+${list(vectors)}
+        ],
+        edges: [
+${list(edges)}
+        ],
+        queries: [
+${list(describeQueries(queries))}
+        ]
+    }
+`;
+}
+
+const schema = readFileSync(SCHEMA_PATH, 'utf8');
+const queries = readFileSync(QUERIES_PATH, 'utf8');
+
+const start = queries.indexOf('// Static description of the graph for agent self-discovery');
+const end = queries.indexOf('\n\n', queries.indexOf('QUERY DescribeSchema() =>')) + 1;
+if (start === -1 || end === 0) {
+  console.error(`DescribeSchema not found in ${QUERIES_PATH}`);
+  process.exit(1);
+}
+
+const updated = queries.slice(0, start) + render(schema, queries) + queries.slice(end);
+
+if (process.argv.includes('--check')) {
+  if (updated !== queries) {
+    console.error(`DescribeSchema in ${QUERIES_PATH} is out of date; run node scripts/generate-describe-schema.mjs`);
+    process.exit(1);
+  }
+  console.log('DescribeSchema is up to date');
+} else {
+  writeFileSync(QUERIES_PATH, updated);
+}